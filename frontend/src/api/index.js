@@ -31,6 +31,9 @@ api.interceptors.response.use(
   }
 );
 
+// 管理接口的鉴权头
+const adminHeaders = (token) => ({ Authorization: `Bearer ${token}` });
+
 // API 方法
 export const serverApi = {
   // 获取系统静态信息
@@ -41,6 +44,14 @@ export const serverApi = {
   
   // 服务健康检查
   checkServiceHealth: (serviceId) => api.get(`/services/${serviceId}/health`),
+
+  // 配置管理，需要服务端 server.admin_token 对应的令牌
+  config: {
+    // 当前生效的配置、被环境变量或命令行覆盖的键，以及是否需要重启
+    get: (token) => api.get('/admin/config', { headers: adminHeaders(token) }),
+    // JSON Merge Patch：对象逐键合并，null 删除键，数组（如 services.items）整体替换；不能修改 server 和 files
+    update: (token, patch) => api.patch('/admin/config', patch, { headers: adminHeaders(token) }),
+  },
   
   // Docker 容器操作（预留）
   dockerContainers: {
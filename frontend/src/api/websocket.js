@@ -1,4 +1,13 @@
 import logger from '../utils/logger';
+import { applyPatch } from '../utils/jsonPatch';
+
+// /api/stream 的事件类型，与服务端主题一致
+const STREAM_TOPICS = [
+  'cpu', 'memory', 'disks', 'network', 'pressure', 'sensors',
+  'gpu', 'ports', 'connections', 'processes', 'docker',
+];
+// WebSocket 从未连上时，重试这么多次后改用 SSE（部分代理不支持 WebSocket）
+const SSE_FALLBACK_ATTEMPTS = 2;
 
 class WebSocketManager {
   constructor() {
@@ -13,6 +22,13 @@ class WebSocketManager {
       connection: [],
     };
     this.isIntentionallyClosed = false;
+    // 订阅的主题和推送间隔，服务端只推送这些主题的增量
+    this.subscription = { topics: ['*'], interval_ms: null };
+    // 由快照和增量合成的当前数据
+    this.state = null;
+    this.hasConnected = false;
+    this.eventSource = null;
+    this.lastEventId = null;
   }
 
   // 计算重连延迟（指数退避）
@@ -39,14 +55,16 @@ class WebSocketManager {
 
     this.ws.onopen = () => {
       logger.info('WebSocket connected');
+      this.hasConnected = true;
       this.reconnectAttempts = 0;
+      this.state = null;
+      this.send({ type: 'subscribe', ...this.subscription });
       this.notifyConnectionListeners(true);
     };
 
     this.ws.onmessage = (event) => {
       try {
-        const data = JSON.parse(event.data);
-        this.notifyMessageListeners(data);
+        this.handleMessage(JSON.parse(event.data));
       } catch (error) {
         logger.error('Failed to parse WebSocket message:', error);
       }
@@ -59,6 +77,12 @@ class WebSocketManager {
     this.ws.onclose = () => {
       logger.info('WebSocket disconnected');
       this.notifyConnectionListeners(false);
+
+      if (!this.isIntentionallyClosed && !this.hasConnected && this.reconnectAttempts >= SSE_FALLBACK_ATTEMPTS) {
+        logger.info('WebSocket unavailable, falling back to Server-Sent Events');
+        this.connectEventSource();
+        return;
+      }
       
       if (!this.isIntentionallyClosed && this.reconnectAttempts < this.maxReconnectAttempts) {
         const delay = this.getReconnectDelay();
@@ -71,14 +95,106 @@ class WebSocketManager {
     };
   }
 
+  // 通过 /api/stream 接收数据，断线后由浏览器按 Last-Event-ID 自动续传
+  connectEventSource() {
+    const topics = this.subscription.topics.join(',');
+    this.eventSource = new EventSource(`/api/stream?topics=${encodeURIComponent(topics)}`);
+    this.state = null;
+
+    this.eventSource.onopen = () => {
+      logger.info('Event stream connected');
+      this.notifyConnectionListeners(true);
+    };
+
+    this.eventSource.onerror = () => {
+      logger.warn('Event stream disconnected, browser will retry');
+      this.notifyConnectionListeners(false);
+    };
+
+    this.eventSource.addEventListener('lagged', (event) => {
+      logger.warn('Event stream lagged:', event.data);
+    });
+
+    // 服务端重启或断点已过期，无法续传：丢弃旧状态，随后会收到最近一次的完整数据
+    this.eventSource.addEventListener('reset', (event) => {
+      logger.warn('Event stream reset:', event.data);
+      this.state = null;
+      this.lastEventId = null;
+    });
+
+    STREAM_TOPICS.forEach(topic => {
+      this.eventSource.addEventListener(topic, (event) => {
+        try {
+          this.state = { ...this.state, ...JSON.parse(event.data) };
+        } catch (error) {
+          logger.error('Failed to parse stream event:', error);
+          return;
+        }
+        // 同一次采样只有最后一个事件带新的 id，收到后再通知，避免每个主题各触发一次渲染
+        if (event.lastEventId !== this.lastEventId) {
+          this.lastEventId = event.lastEventId;
+          this.notifyMessageListeners(this.state);
+        }
+      });
+    });
+  }
+
   disconnect() {
     this.isIntentionallyClosed = true;
+    if (this.eventSource) {
+      this.eventSource.close();
+      this.eventSource = null;
+    }
     if (this.ws) {
       this.ws.close();
       this.ws = null;
     }
   }
 
+  handleMessage(message) {
+    switch (message.type) {
+      case 'snapshot':
+        this.state = message.data;
+        break;
+      case 'delta':
+        if (!this.state) {
+          return;
+        }
+        try {
+          this.state = applyPatch(this.state, message.patch);
+        } catch (error) {
+          // 本地状态与服务端不一致，请求重新发送快照
+          logger.warn('Failed to apply delta, requesting resync:', error);
+          this.state = null;
+          this.send({ type: 'resync' });
+          return;
+        }
+        break;
+      case 'lagged':
+        // 服务端已跳到最新数据，下一条增量照常应用
+        logger.warn(`Realtime updates lagged, server skipped ${message.skipped} updates`);
+        return;
+      case 'error':
+        logger.error('WebSocket server error:', message.message);
+        return;
+      default:
+        // 未订阅时服务端推送完整数据
+        this.state = message;
+    }
+    this.notifyMessageListeners(this.state);
+  }
+
+  // 更改订阅的主题（如 ['cpu', 'memory']，'*' 表示全部）和推送间隔
+  subscribe(topics, intervalMs = null) {
+    this.subscription = { topics, interval_ms: intervalMs };
+    if (this.eventSource) {
+      this.eventSource.close();
+      this.connectEventSource();
+    } else if (this.ws && this.ws.readyState === WebSocket.OPEN) {
+      this.send({ type: 'subscribe', ...this.subscription });
+    }
+  }
+
   send(data) {
     if (this.ws && this.ws.readyState === WebSocket.OPEN) {
       this.ws.send(JSON.stringify(data));
@@ -3,6 +3,9 @@ import useServerStore from '../../store/serverStore';
 import Card, { CardBody } from '../common/Card';
 import { formatBytes } from '../../utils/helpers';
 
+// 部分驱动（Intel、N/A 的 NVIDIA 字段）不提供显存数据
+const hasMemory = (gpu) => gpu.memory_total_mb > 0 && gpu.memory_used_mb !== null && gpu.memory_used_mb !== undefined;
+
 const GpuWidget = () => {
   const gpuList = useServerStore((state) => state.realtimeData.gpu);
 
@@ -36,7 +39,7 @@ const GpuWidget = () => {
         
         <div className="space-y-4">
           {gpuList.map((gpu, index) => (
-            <div key={index} className="space-y-3">
+            <div key={gpu.id} className="space-y-3">
               {gpuList.length > 1 && (
                 <h3 className="text-md font-medium text-gray-700 dark:text-gray-300">
                   GPU {gpu.index}: {gpu.name}
@@ -44,27 +47,31 @@ const GpuWidget = () => {
               )}
               
               <div className="grid grid-cols-2 md:grid-cols-3 gap-4">
-                <div>
-                  <p className="text-sm text-gray-500 dark:text-gray-400">温度</p>
-                  <p className={`text-lg font-medium ${
-                    gpu.temperature_celsius > 80 ? 'text-red-500' :
-                    gpu.temperature_celsius > 70 ? 'text-yellow-500' :
-                    'text-green-500'
-                  }`}>
-                    {gpu.temperature_celsius}°C
-                  </p>
-                </div>
+                {gpu.temperature_celsius !== null && gpu.temperature_celsius !== undefined && (
+                  <div>
+                    <p className="text-sm text-gray-500 dark:text-gray-400">温度</p>
+                    <p className={`text-lg font-medium ${
+                      gpu.temperature_celsius > 80 ? 'text-red-500' :
+                      gpu.temperature_celsius > 70 ? 'text-yellow-500' :
+                      'text-green-500'
+                    }`}>
+                      {gpu.temperature_celsius}°C
+                    </p>
+                  </div>
+                )}
                 
-                <div>
-                  <p className="text-sm text-gray-500 dark:text-gray-400">使用率</p>
-                  <p className={`text-lg font-medium ${
-                    gpu.utilization_percent > 90 ? 'text-red-500' :
-                    gpu.utilization_percent > 70 ? 'text-yellow-500' :
-                    'text-green-500'
-                  }`}>
-                    {gpu.utilization_percent}%
-                  </p>
-                </div>
+                {gpu.utilization_percent !== null && gpu.utilization_percent !== undefined && (
+                  <div>
+                    <p className="text-sm text-gray-500 dark:text-gray-400">使用率</p>
+                    <p className={`text-lg font-medium ${
+                      gpu.utilization_percent > 90 ? 'text-red-500' :
+                      gpu.utilization_percent > 70 ? 'text-yellow-500' :
+                      'text-green-500'
+                    }`}>
+                      {gpu.utilization_percent}%
+                    </p>
+                  </div>
+                )}
                 
                 {gpu.power_draw_watts !== null && gpu.power_draw_watts !== undefined && (
                   <div>
@@ -84,28 +91,32 @@ const GpuWidget = () => {
                   </div>
                 )}
                 
-                <div>
-                  <p className="text-sm text-gray-500 dark:text-gray-400">显存</p>
-                  <p className="text-lg font-medium">
-                    {((gpu.memory_used_mb / gpu.memory_total_mb) * 100).toFixed(1)}%
-                  </p>
-                </div>
+                {hasMemory(gpu) && (
+                  <div>
+                    <p className="text-sm text-gray-500 dark:text-gray-400">显存</p>
+                    <p className="text-lg font-medium">
+                      {((gpu.memory_used_mb / gpu.memory_total_mb) * 100).toFixed(1)}%
+                    </p>
+                  </div>
+                )}
               </div>
               
-              <div>
-                <div className="flex justify-between text-sm mb-1">
-                  <span className="text-gray-500 dark:text-gray-400">显存使用</span>
-                  <span>
-                    {formatBytes(gpu.memory_used_mb * 1024 * 1024)} / {formatBytes(gpu.memory_total_mb * 1024 * 1024)}
-                  </span>
-                </div>
-                <div className="h-2 bg-gray-200 dark:bg-gray-700 rounded-full overflow-hidden">
-                  <div 
-                    className="h-full bg-gradient-to-r from-green-500 via-yellow-500 to-red-500 transition-all duration-300"
-                    style={{ width: `${(gpu.memory_used_mb / gpu.memory_total_mb) * 100}%` }}
-                  />
+              {hasMemory(gpu) && (
+                <div>
+                  <div className="flex justify-between text-sm mb-1">
+                    <span className="text-gray-500 dark:text-gray-400">显存使用</span>
+                    <span>
+                      {formatBytes(gpu.memory_used_mb * 1024 * 1024)} / {formatBytes(gpu.memory_total_mb * 1024 * 1024)}
+                    </span>
+                  </div>
+                  <div className="h-2 bg-gray-200 dark:bg-gray-700 rounded-full overflow-hidden">
+                    <div 
+                      className="h-full bg-gradient-to-r from-green-500 via-yellow-500 to-red-500 transition-all duration-300"
+                      style={{ width: `${(gpu.memory_used_mb / gpu.memory_total_mb) * 100}%` }}
+                    />
+                  </div>
                 </div>
-              </div>
+              )}
               
               {(gpu.graphics_clock_mhz !== null || gpu.memory_clock_mhz !== null) && (
                 <div className="flex gap-4 text-sm">
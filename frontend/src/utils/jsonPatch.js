@@ -0,0 +1,43 @@
+// 应用服务端推送的 JSON Patch（RFC 6902），仅支持 add / remove / replace
+
+function parsePointer(path) {
+  if (path === '') return [];
+  return path.split('/').slice(1).map(token => token.replace(/~1/g, '/').replace(/~0/g, '~'));
+}
+
+function applyOperation(document, { op, path, value }) {
+  const tokens = parsePointer(path);
+  if (tokens.length === 0) {
+    if (op === 'remove') throw new Error('Cannot remove document root');
+    return value;
+  }
+
+  let parent = document;
+  for (const token of tokens.slice(0, -1)) {
+    parent = parent?.[Array.isArray(parent) ? Number(token) : token];
+    if (parent === null || typeof parent !== 'object') {
+      throw new Error(`Path not found: ${path}`);
+    }
+  }
+
+  const last = tokens[tokens.length - 1];
+  if (Array.isArray(parent)) {
+    const index = last === '-' ? parent.length : Number(last);
+    if (op === 'add') parent.splice(index, 0, value);
+    else if (op === 'remove') parent.splice(index, 1);
+    else if (op === 'replace') parent[index] = value;
+    else throw new Error(`Unsupported op: ${op}`);
+  } else if (op === 'add' || op === 'replace') {
+    parent[last] = value;
+  } else if (op === 'remove') {
+    delete parent[last];
+  } else {
+    throw new Error(`Unsupported op: ${op}`);
+  }
+  return document;
+}
+
+// 返回新对象，不修改传入的 document（store 依赖引用变化触发更新）
+export function applyPatch(document, patch) {
+  return patch.reduce(applyOperation, structuredClone(document));
+}
@@ -0,0 +1,130 @@
+use crate::config::{ConfigOverrides, CONFIG_PATH};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// 家庭服务器导航页与系统监控
+#[derive(Debug, Parser)]
+#[command(name = "index-rs", version)]
+pub struct Cli {
+    /// 配置文件路径
+    #[arg(short, long, global = true, default_value = CONFIG_PATH)]
+    pub config: PathBuf,
+
+    /// 前端静态文件目录，覆盖配置中的 server.static_dir
+    #[arg(long, global = true)]
+    pub static_dir: Option<String>,
+
+    /// 监听地址，覆盖配置中的 server.host
+    #[arg(long, global = true)]
+    pub host: Option<String>,
+
+    /// 监听端口，覆盖配置中的 server.port
+    #[arg(long, global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    pub port: Option<u16>,
+
+    /// 日志级别（如 debug），或 EnvFilter 语法的过滤规则；默认读取 RUST_LOG
+    #[arg(long, global = true)]
+    pub log_level: Option<String>,
+
+    /// 配置文件缺失或无效时使用内置默认配置
+    #[arg(long, global = true)]
+    pub allow_default_config: bool,
+
+    /// 同 check-config 子命令
+    #[arg(long, hide = true)]
+    check_config: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum Command {
+    /// 启动服务（默认）
+    Serve,
+    /// 校验配置文件，有问题时以非零状态退出
+    CheckConfig,
+    /// 以 TOML 输出内置默认配置
+    PrintDefaultConfig,
+    /// 采集一次系统数据，以 JSON 输出后退出
+    Snapshot {
+        /// 速率类数据两次采样的间隔（毫秒）
+        #[arg(long, default_value_t = 1000)]
+        sample_ms: u64,
+        /// 格式化输出
+        #[arg(long)]
+        pretty: bool,
+    },
+}
+
+impl Cli {
+    /// 要执行的子命令，未指定时为 serve
+    pub fn command(&self) -> Command {
+        match self.command {
+            Some(command) => command,
+            None if self.check_config => Command::CheckConfig,
+            None => Command::Serve,
+        }
+    }
+
+    pub fn overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            host: self.host.clone(),
+            port: self.port,
+            static_dir: self.static_dir.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("index-rs").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn definition_is_valid() {
+        <Cli as CommandFactory>::command().debug_assert();
+    }
+
+    #[test]
+    fn defaults_to_serve() {
+        let cli = parse(&[]).unwrap();
+        assert!(matches!(cli.command(), Command::Serve));
+        assert_eq!(cli.config, PathBuf::from(CONFIG_PATH));
+        assert!(cli.overrides().sources().is_empty());
+    }
+
+    #[test]
+    fn hidden_check_config_flag() {
+        let cli = parse(&["--check-config", "-c", "/etc/index-rs.toml"]).unwrap();
+        assert!(matches!(cli.command(), Command::CheckConfig));
+        assert_eq!(cli.config, PathBuf::from("/etc/index-rs.toml"));
+        // 显式的子命令优先
+        let cli = parse(&["--check-config", "print-default-config"]).unwrap();
+        assert!(matches!(cli.command(), Command::PrintDefaultConfig));
+    }
+
+    #[test]
+    fn validates_port() {
+        assert!(parse(&["--port", "0"]).is_err());
+        assert!(parse(&["--port", "65536"]).is_err());
+        // 全局参数也可以写在子命令之后
+        let cli = parse(&["check-config", "--port", "8080", "--host", "127.0.0.1"]).unwrap();
+        assert!(matches!(cli.command(), Command::CheckConfig));
+        let overrides = cli.overrides();
+        assert_eq!((overrides.host.as_deref(), overrides.port), (Some("127.0.0.1"), Some(8080)));
+    }
+
+    #[test]
+    fn snapshot_options() {
+        let cli = parse(&["snapshot"]).unwrap();
+        assert!(matches!(cli.command(), Command::Snapshot { sample_ms: 1000, pretty: false }));
+        let cli = parse(&["snapshot", "--sample-ms", "250", "--pretty"]).unwrap();
+        assert!(matches!(cli.command(), Command::Snapshot { sample_ms: 250, pretty: true }));
+        assert!(parse(&["snapshot", "--sample-ms", "soon"]).is_err());
+    }
+}
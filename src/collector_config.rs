@@ -1,16 +1,19 @@
 use crate::collector_utils::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// 全局配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct GlobalConfig {
+    #[serde(default)]
     pub collectors: CollectorsConfig,
+    #[serde(default)]
     pub performance: PerformanceConfig,
 }
 
 /// 采集器配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CollectorsConfig {
     /// 数据采集间隔（秒）
     #[serde(default = "default_collect_interval")]
@@ -24,6 +27,22 @@ pub struct CollectorsConfig {
     #[serde(default = "default_true")]
     pub enable_ports: bool,
     
+    /// 是否启用磁盘 I/O 监控
+    #[serde(default = "default_true")]
+    pub enable_disk_io: bool,
+    
+    /// 是否启用 SMART 磁盘健康监控
+    #[serde(default = "default_true")]
+    pub enable_smart: bool,
+    
+    /// 是否启用 RAID/ZFS/Btrfs 存储池监控
+    #[serde(default = "default_true")]
+    pub enable_storage_pools: bool,
+    
+    /// 是否启用活动连接跟踪
+    #[serde(default = "default_true")]
+    pub enable_connections: bool,
+    
     /// 是否启用进程监控
     #[serde(default = "default_true")]
     pub enable_processes: bool,
@@ -36,6 +55,10 @@ pub struct CollectorsConfig {
     #[serde(default = "default_true")]
     pub enable_docker: bool,
     
+    /// 网络总量是否排除虚拟网卡（lo、docker0、veth* 等）
+    #[serde(default)]
+    pub exclude_virtual_interfaces: bool,
+    
     /// 命令超时时间（秒）
     #[serde(default = "default_command_timeout")]
     pub command_timeout_secs: u64,
@@ -43,12 +66,36 @@ pub struct CollectorsConfig {
     /// 最大进程数
     #[serde(default = "default_max_processes")]
     pub max_processes: usize,
+    
+    /// 连接明细最大数量（聚合统计不受影响）
+    #[serde(default = "default_max_connections")]
+    pub max_connections: usize,
+    
+    /// 各采集器的独立调度，键为采集器名称（cpu、memory、disks、docker 等）
+    #[serde(default)]
+    pub schedules: HashMap<String, CollectorScheduleConfig>,
+}
+
+/// 单个采集器的调度配置，未设置的项使用默认值
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct CollectorScheduleConfig {
+    /// 是否启用，未设置时沿用对应的 enable_* 开关
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    
+    /// 采集间隔（秒）
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    
+    /// 单次采集超时（秒），超时后保留上一次的结果
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 /// 性能配置
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct PerformanceConfig {
-    /// GPU 缓存 TTL（秒）
+    /// GPU 采集间隔（秒），gpu 采集器的默认间隔
     #[serde(default = "default_gpu_cache_ttl")]
     pub gpu_cache_ttl_secs: u64,
     
@@ -60,7 +107,27 @@ pub struct PerformanceConfig {
     #[serde(default = "default_port_scan_timeout")]
     pub port_scan_timeout_secs: u64,
     
-    /// 历史数据保留数量
+    /// SMART 采集间隔（秒），smart 采集器的默认间隔
+    #[serde(default = "default_smart_cache_ttl")]
+    pub smart_cache_ttl_secs: u64,
+    
+    /// 存储池状态采集间隔（秒），storage_pools 采集器的默认间隔
+    #[serde(default = "default_storage_pools_cache_ttl")]
+    pub storage_pools_cache_ttl_secs: u64,
+    
+    /// 网卡地址缓存 TTL（秒）
+    #[serde(default = "default_interface_addr_cache_ttl")]
+    pub interface_addr_cache_ttl_secs: u64,
+    
+    /// 反向 DNS 缓存 TTL（秒）
+    #[serde(default = "default_dns_cache_ttl")]
+    pub dns_cache_ttl_secs: u64,
+    
+    /// 目录大小分析结果缓存 TTL（秒）
+    #[serde(default = "default_disk_usage_cache_ttl")]
+    pub disk_usage_cache_ttl_secs: u64,
+    
+    /// 保留的实时数据条数，供 SSE 按 Last-Event-ID 续传
     #[serde(default = "default_history_size")]
     pub history_size: usize,
 }
@@ -70,9 +137,15 @@ fn default_collect_interval() -> u64 { 1 }
 fn default_true() -> bool { true }
 fn default_command_timeout() -> u64 { 5 }
 fn default_max_processes() -> usize { 20 }
+fn default_max_connections() -> usize { 200 }
 fn default_gpu_cache_ttl() -> u64 { 5 }
 fn default_sensors_cache_ttl() -> u64 { 10 }
 fn default_port_scan_timeout() -> u64 { 3 }
+fn default_smart_cache_ttl() -> u64 { 300 }
+fn default_storage_pools_cache_ttl() -> u64 { 10 }
+fn default_interface_addr_cache_ttl() -> u64 { 30 }
+fn default_dns_cache_ttl() -> u64 { 300 }
+fn default_disk_usage_cache_ttl() -> u64 { 600 }
 fn default_history_size() -> usize { 60 }
 
 impl Default for GlobalConfig {
@@ -90,11 +163,18 @@ impl Default for CollectorsConfig {
             collect_interval_secs: default_collect_interval(),
             enable_gpu: default_true(),
             enable_ports: default_true(),
+            enable_disk_io: default_true(),
+            enable_smart: default_true(),
+            enable_storage_pools: default_true(),
+            enable_connections: default_true(),
             enable_processes: default_true(),
             enable_sensors: default_true(),
             enable_docker: default_true(),
+            exclude_virtual_interfaces: false,
             command_timeout_secs: default_command_timeout(),
             max_processes: default_max_processes(),
+            max_connections: default_max_connections(),
+            schedules: HashMap::new(),
         }
     }
 }
@@ -105,19 +185,26 @@ impl Default for PerformanceConfig {
             gpu_cache_ttl_secs: default_gpu_cache_ttl(),
             sensors_cache_ttl_secs: default_sensors_cache_ttl(),
             port_scan_timeout_secs: default_port_scan_timeout(),
+            smart_cache_ttl_secs: default_smart_cache_ttl(),
+            storage_pools_cache_ttl_secs: default_storage_pools_cache_ttl(),
+            interface_addr_cache_ttl_secs: default_interface_addr_cache_ttl(),
+            dns_cache_ttl_secs: default_dns_cache_ttl(),
+            disk_usage_cache_ttl_secs: default_disk_usage_cache_ttl(),
             history_size: default_history_size(),
         }
     }
 }
 
-impl From<GlobalConfig> for CollectorConfig {
-    fn from(config: GlobalConfig) -> Self {
+impl From<&GlobalConfig> for CollectorConfig {
+    fn from(config: &GlobalConfig) -> Self {
         CollectorConfig {
             command_timeout: Duration::from_secs(config.collectors.command_timeout_secs),
-            gpu_cache_ttl: Duration::from_secs(config.performance.gpu_cache_ttl_secs),
             sensors_cache_ttl: Duration::from_secs(config.performance.sensors_cache_ttl_secs),
             port_scan_timeout: Duration::from_secs(config.performance.port_scan_timeout_secs),
             max_processes: config.collectors.max_processes,
+            max_connections: config.collectors.max_connections,
+            interface_addr_cache_ttl: Duration::from_secs(config.performance.interface_addr_cache_ttl_secs),
+            dns_cache_ttl: Duration::from_secs(config.performance.dns_cache_ttl_secs),
             enable_fallback: true,
         }
     }
@@ -0,0 +1,299 @@
+use crate::collector_config::GlobalConfig;
+use crate::models::RealtimeData;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, timeout, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+/// 各采集器最近一次结果汇总成的快照，由推送任务定期广播
+pub type SharedSnapshot = Arc<RwLock<RealtimeData>>;
+
+/// 可独立调度的采集器
+///
+/// 每个实现运行在自己的任务中，按各自的间隔采集，结果通过 `apply`
+/// 写入共享快照中属于自己的字段。慢采集器（docker、smartctl）不会拖慢其它采集器。
+#[async_trait]
+pub trait Collector: Send + 'static {
+    /// 单次采集的结果，默认值用于采集器被禁用时清空快照中的字段
+    type Output: Send + Default;
+
+    /// 采集器名称，对应配置 `[monitoring.collectors.schedules.<name>]`
+    fn name(&self) -> &'static str;
+
+    /// 未在调度配置中指定 enabled 时是否启用
+    fn enabled_by_default(&self, _config: &GlobalConfig) -> bool {
+        true
+    }
+
+    /// 未在调度配置中指定 interval_secs 时的采集间隔
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.collectors.collect_interval_secs)
+    }
+
+    /// 采集一次；`latest` 为当前快照，供依赖其它采集器结果的实现读取
+    async fn collect(&mut self, latest: &SharedSnapshot) -> Self::Output;
+
+    /// 将结果写入快照
+    fn apply(output: Self::Output, data: &mut RealtimeData);
+}
+
+/// 采集器的最终调度参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollectorSchedule {
+    pub enabled: bool,
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl CollectorSchedule {
+    /// 合并调度配置和采集器默认值，超时默认为间隔与命令超时中较大者的两倍
+    pub fn resolve<C: Collector>(collector: &C, config: &GlobalConfig) -> Self {
+        let overrides = config.collectors.schedules.get(collector.name()).cloned().unwrap_or_default();
+        let interval = overrides
+            .interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or_else(|| collector.default_interval(config))
+            .max(Duration::from_secs(1));
+        let default_timeout = interval.max(Duration::from_secs(config.collectors.command_timeout_secs)) * 2;
+
+        Self {
+            enabled: overrides.enabled.unwrap_or_else(|| collector.enabled_by_default(config)),
+            interval,
+            timeout: overrides.timeout_secs.map(Duration::from_secs).unwrap_or(default_timeout),
+        }
+    }
+}
+
+/// 采集器的运行方式
+#[derive(Debug, Clone, Copy)]
+pub enum RunMode {
+    /// 按调度持续运行
+    Scheduled,
+    /// 相隔 `sample` 采集两次后结束，第一次用于建立速率基线（snapshot 子命令）
+    Once { sample: Duration },
+}
+
+/// 在独立任务中运行采集器
+///
+/// 按调度运行时采集器一直存在，配置变化后只有自己的调度（启用、间隔、超时）变了才重新计时，
+/// 速率基线等内部状态保留；被禁用期间清空快照中对应的字段。只运行一次且被禁用时返回 None。
+pub async fn spawn_collector<C: Collector>(
+    mut collector: C,
+    mut config_rx: watch::Receiver<GlobalConfig>,
+    snapshot: SharedSnapshot,
+    mode: RunMode,
+) -> Option<JoinHandle<()>> {
+    let name = collector.name();
+    let RunMode::Once { sample } = mode else {
+        return Some(tokio::spawn(async move {
+            loop {
+                let schedule = CollectorSchedule::resolve(&collector, &config_rx.borrow_and_update());
+                if !schedule.enabled {
+                    info!("Collector {} disabled", name);
+                    // 配置热加载后停用的采集器不应留下旧数据
+                    C::apply(C::Output::default(), &mut *snapshot.write().await);
+                    if config_rx.changed().await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+
+                info!("Collector {} scheduled: {:?}", name, schedule);
+                let mut ticker = interval(schedule.interval);
+                // 采集耗时超过间隔时顺延，不连续补采
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+                loop {
+                    tokio::select! {
+                        _ = ticker.tick() => collect_round(&mut collector, &snapshot, schedule.timeout).await,
+                        Ok(()) = config_rx.changed() => {
+                            if CollectorSchedule::resolve(&collector, &config_rx.borrow()) != schedule {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+    };
+
+    let schedule = CollectorSchedule::resolve(&collector, &config_rx.borrow());
+    if !schedule.enabled {
+        debug!("Collector {} disabled", name);
+        return None;
+    }
+    debug!("Collector {} running once: {:?}", name, schedule);
+    Some(tokio::spawn(async move {
+        collect_round(&mut collector, &snapshot, schedule.timeout).await;
+        tokio::time::sleep(sample).await;
+        collect_round(&mut collector, &snapshot, schedule.timeout).await;
+    }))
+}
+
+async fn collect_round<C: Collector>(collector: &mut C, snapshot: &SharedSnapshot, limit: Duration) {
+    let name = collector.name();
+    let started = Instant::now();
+
+    match timeout(limit, collector.collect(snapshot)).await {
+        Ok(output) => C::apply(output, &mut *snapshot.write().await),
+        // 超时保留快照中上一次的结果
+        Err(_) => warn!("Collector {} timed out after {:?}", name, limit),
+    }
+    debug!("Collector {} took {:?}", name, started.elapsed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector_config::CollectorScheduleConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 记录采集次数，把固定的值写入 uptime_secs
+    struct FakeCollector {
+        calls: Arc<AtomicUsize>,
+        value: u64,
+        delay: Duration,
+    }
+
+    impl FakeCollector {
+        fn new(value: u64) -> Self {
+            Self { calls: Arc::new(AtomicUsize::new(0)), value, delay: Duration::ZERO }
+        }
+    }
+
+    #[async_trait]
+    impl Collector for FakeCollector {
+        type Output = u64;
+
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+            config.collectors.enable_gpu
+        }
+
+        async fn collect(&mut self, _latest: &SharedSnapshot) -> u64 {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.value
+        }
+
+        fn apply(output: u64, data: &mut RealtimeData) {
+            data.uptime_secs = output;
+        }
+    }
+
+    fn config_with(schedule: CollectorScheduleConfig) -> GlobalConfig {
+        let mut config = GlobalConfig::default();
+        config.collectors.collect_interval_secs = 3;
+        config.collectors.command_timeout_secs = 5;
+        config.collectors.schedules.insert("fake".to_string(), schedule);
+        config
+    }
+
+    #[test]
+    fn resolves_defaults() {
+        let schedule = CollectorSchedule::resolve(&FakeCollector::new(0), &config_with(Default::default()));
+        assert_eq!(
+            schedule,
+            CollectorSchedule { enabled: true, interval: Duration::from_secs(3), timeout: Duration::from_secs(10) }
+        );
+    }
+
+    #[test]
+    fn schedule_overrides_defaults() {
+        let config = config_with(CollectorScheduleConfig {
+            enabled: None,
+            interval_secs: Some(30),
+            timeout_secs: None,
+        });
+        let schedule = CollectorSchedule::resolve(&FakeCollector::new(0), &config);
+        assert_eq!(schedule.interval, Duration::from_secs(30));
+        assert_eq!(schedule.timeout, Duration::from_secs(60));
+
+        let config = config_with(CollectorScheduleConfig {
+            enabled: Some(false),
+            interval_secs: Some(30),
+            timeout_secs: Some(4),
+        });
+        let schedule = CollectorSchedule::resolve(&FakeCollector::new(0), &config);
+        assert!(!schedule.enabled);
+        assert_eq!(schedule.timeout, Duration::from_secs(4));
+
+        // 未单独配置时沿用采集器自己的开关
+        let mut config = config_with(Default::default());
+        config.collectors.enable_gpu = false;
+        assert!(!CollectorSchedule::resolve(&FakeCollector::new(0), &config).enabled);
+    }
+
+    #[tokio::test]
+    async fn timeout_keeps_last_value() {
+        let snapshot = SharedSnapshot::default();
+        snapshot.write().await.uptime_secs = 7;
+        let mut collector = FakeCollector { delay: Duration::from_secs(5), ..FakeCollector::new(42) };
+        collect_round(&mut collector, &snapshot, Duration::from_millis(20)).await;
+        assert_eq!(snapshot.read().await.uptime_secs, 7);
+
+        collector.delay = Duration::ZERO;
+        collect_round(&mut collector, &snapshot, Duration::from_millis(20)).await;
+        assert_eq!(snapshot.read().await.uptime_secs, 42);
+    }
+
+    #[tokio::test]
+    async fn restarts_only_when_own_schedule_changes() {
+        let collector = FakeCollector::new(42);
+        let calls = collector.calls.clone();
+        let snapshot = SharedSnapshot::default();
+        let (config_tx, config_rx) = watch::channel(config_with(Default::default()));
+        let task = spawn_collector(collector, config_rx, snapshot.clone(), RunMode::Scheduled).await.unwrap();
+
+        // 计时器立即触发第一次采集
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(snapshot.read().await.uptime_secs, 42);
+
+        // 其它采集器的调度变化不会让它重新计时
+        config_tx.send_modify(|config| {
+            config.collectors.schedules.insert("other".to_string(), CollectorScheduleConfig {
+                interval_secs: Some(1),
+                ..Default::default()
+            });
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 自己的间隔变了，重新计时并立即采集
+        config_tx.send_modify(|config| {
+            config.collectors.schedules.insert("fake".to_string(), CollectorScheduleConfig {
+                interval_secs: Some(10),
+                ..Default::default()
+            });
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 停用后清空快照中的字段
+        config_tx.send_modify(|config| {
+            config.collectors.schedules.insert("fake".to_string(), CollectorScheduleConfig {
+                enabled: Some(false),
+                ..Default::default()
+            });
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(snapshot.read().await.uptime_secs, 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn disabled_collector_does_not_run_once() {
+        let config = config_with(CollectorScheduleConfig { enabled: Some(false), ..Default::default() });
+        let (_config_tx, config_rx) = watch::channel(config);
+        let mode = RunMode::Once { sample: Duration::ZERO };
+        assert!(spawn_collector(FakeCollector::new(42), config_rx, SharedSnapshot::default(), mode).await.is_none());
+    }
+}
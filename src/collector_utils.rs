@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -46,21 +46,30 @@ impl<T> CollectionResult<T> {
 /// 数据缓存
 pub struct DataCache<T: Clone> {
     data: Arc<RwLock<HashMap<String, (T, DateTime<Utc>)>>>,
-    ttl: Duration,
+    ttl: std::sync::Mutex<Duration>,
 }
 
 impl<T: Clone> DataCache<T> {
     pub fn new(ttl: Duration) -> Self {
         Self {
             data: Arc::new(RwLock::new(HashMap::new())),
-            ttl,
+            ttl: std::sync::Mutex::new(ttl),
         }
     }
 
+    fn ttl(&self) -> Duration {
+        *self.ttl.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 修改缓存时间（配置热加载），已缓存的条目按新的时间判断是否过期
+    pub fn set_ttl(&self, ttl: Duration) {
+        *self.ttl.lock().unwrap_or_else(|e| e.into_inner()) = ttl;
+    }
+
     pub async fn get(&self, key: &str) -> Option<T> {
         let cache = self.data.read().await;
         if let Some((data, timestamp)) = cache.get(key) {
-            if Utc::now().signed_duration_since(*timestamp).to_std().unwrap_or(Duration::MAX) < self.ttl {
+            if Utc::now().signed_duration_since(*timestamp).to_std().unwrap_or(Duration::MAX) < self.ttl() {
                 return Some(data.clone());
             }
         }
@@ -73,25 +82,117 @@ impl<T: Clone> DataCache<T> {
         
         // 清理过期条目
         let now = Utc::now();
+        let ttl = self.ttl();
         cache.retain(|_, (_, timestamp)| {
-            now.signed_duration_since(*timestamp).to_std().unwrap_or(Duration::MAX) < self.ttl * 2
+            now.signed_duration_since(*timestamp).to_std().unwrap_or(Duration::MAX) < ttl * 2
         });
     }
 }
 
+/// 超过该时间未更新的速率计数器视为已消失（网卡、磁盘、容器被移除）
+pub const RATE_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// 计数器速率计算
+///
+/// 记录每个计数器上一次的数值和单调时间戳，按实际经过的时间计算每秒速率，
+/// 不依赖采集间隔恰好为一秒。
+#[derive(Debug, Default)]
+pub struct RateTracker {
+    samples: HashMap<String, (u64, Instant)>,
+}
+
+impl RateTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录 64 位计数器的新值并返回每秒速率，首次出现的计数器返回 None
+    pub fn rate(&mut self, key: &str, value: u64, now: Instant) -> Option<f64> {
+        self.rate_with(key, value, now, counter_delta)
+    }
+
+    /// 同 `rate`，用于内核中 `unsigned long` 类型的计数器（/proc/diskstats、/proc/vmstat），
+    /// 它们在 32 位系统上于 2^32 处回绕
+    pub fn rate_ulong(&mut self, key: &str, value: u64, now: Instant) -> Option<f64> {
+        if usize::BITS == 32 {
+            self.rate_with(key, value, now, counter_delta_u32)
+        } else {
+            self.rate_with(key, value, now, counter_delta)
+        }
+    }
+
+    /// 同 `rate`，但计数器在已知的 `range` 处回绕（例如 RAPL 的 max_energy_range_uj）
+    pub fn rate_wrapping(&mut self, key: &str, value: u64, range: u64, now: Instant) -> Option<f64> {
+        self.rate_with(key, value, now, |last, current| {
+            if current >= last {
+                current - last
+            } else {
+                range.saturating_sub(last) + current
+            }
+        })
+    }
+
+    fn rate_with(&mut self, key: &str, value: u64, now: Instant, delta: impl Fn(u64, u64) -> u64) -> Option<f64> {
+        let previous = self.samples.insert(key.to_string(), (value, now));
+        let (last_value, last_time) = previous?;
+
+        let elapsed = now.checked_duration_since(last_time)?.as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+
+        Some(delta(last_value, value) as f64 / elapsed)
+    }
+
+    /// 清理长时间未更新的计数器（例如已消失的网卡或容器）
+    pub fn prune(&mut self, max_age: Duration, now: Instant) {
+        self.samples.retain(|_, (_, at)| now.saturating_duration_since(*at) <= max_age);
+    }
+}
+
+/// 64 位计数器两次读数之间的增量
+///
+/// 64 位计数器实际上不会回绕，变小说明计数器被重置（网卡重建、容器重启等），从 0 重新计数。
+fn counter_delta(last: u64, current: u64) -> u64 {
+    current.checked_sub(last).unwrap_or(current)
+}
+
+/// 32 位计数器两次读数之间的增量，处理回绕和重置
+fn counter_delta_u32(last: u64, current: u64) -> u64 {
+    if current >= last {
+        return current - last;
+    }
+
+    // 上次读数接近上限，回绕后的增量较小
+    let u32_max = u32::MAX as u64;
+    if last <= u32_max {
+        let wrapped = (u32_max - last) + current + 1;
+        if wrapped < u32_max / 2 {
+            return wrapped;
+        }
+    }
+
+    // 否则视为重置
+    current
+}
+
 /// 采集器配置
 #[derive(Debug, Clone)]
 pub struct CollectorConfig {
     /// 命令执行超时时间
     pub command_timeout: Duration,
-    /// GPU 数据缓存时间
-    pub gpu_cache_ttl: Duration,
     /// CPU 传感器数据缓存时间
     pub sensors_cache_ttl: Duration,
     /// 端口扫描超时时间
     pub port_scan_timeout: Duration,
     /// 进程采集最大数量
     pub max_processes: usize,
+    /// 连接明细最大数量
+    pub max_connections: usize,
+    /// 网卡地址缓存时间
+    pub interface_addr_cache_ttl: Duration,
+    /// 反向 DNS 缓存时间
+    pub dns_cache_ttl: Duration,
     /// 是否启用降级模式
     pub enable_fallback: bool,
 }
@@ -100,48 +201,70 @@ impl Default for CollectorConfig {
     fn default() -> Self {
         Self {
             command_timeout: Duration::from_secs(5),
-            gpu_cache_ttl: Duration::from_secs(5),
             sensors_cache_ttl: Duration::from_secs(10),
             port_scan_timeout: Duration::from_secs(3),
             max_processes: 20,
+            max_connections: 200,
+            interface_addr_cache_ttl: Duration::from_secs(30),
+            dns_cache_ttl: Duration::from_secs(300),
             enable_fallback: true,
         }
     }
 }
 
+/// 命令的原始执行结果
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
 /// 执行命令并处理超时
 pub async fn execute_command_with_timeout(
     cmd: &str,
     args: &[&str],
     timeout_duration: Duration,
 ) -> Result<String, String> {
+    let output = execute_command_output_with_timeout(cmd, args, timeout_duration).await?;
+    if output.success {
+        Ok(output.stdout)
+    } else {
+        Err(format!("Command failed: {}", output.stderr))
+    }
+}
+
+/// 执行命令并处理超时，不论退出码都返回输出
+///
+/// 用于 smartctl 这类用退出码的位掩码表示检测结果、而非执行失败的命令。
+pub async fn execute_command_output_with_timeout(
+    cmd: &str,
+    args: &[&str],
+    timeout_duration: Duration,
+) -> Result<CommandOutput, String> {
     let args_owned: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    // 超时（或外层采集任务超时）丢弃 future 时结束子进程，避免 ss、getent 等在繁忙主机上越积越多
     let result = timeout(
         timeout_duration,
         tokio::process::Command::new(cmd)
             .args(&args_owned)
+            .kill_on_drop(true)
             .output()
     ).await;
 
     match result {
-        Ok(Ok(output)) => {
-            if output.status.success() {
-                Ok(String::from_utf8_lossy(&output.stdout).to_string())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                Err(format!("Command failed: {}", stderr))
-            }
-        }
+        Ok(Ok(output)) => Ok(CommandOutput {
+            success: output.status.success(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }),
         Ok(Err(e)) => Err(format!("Failed to execute command: {}", e)),
         Err(_) => Err(format!("Command timed out after {:?}", timeout_duration)),
     }
 }
 
-/// 安全地解析数值，带默认值
-pub fn parse_with_default<T: std::str::FromStr>(s: &str, default: T) -> T {
-    s.trim().parse::<T>().unwrap_or(default)
-}
-
 /// 批量执行命令并收集结果
 pub async fn batch_execute_commands(
     commands: Vec<(&str, Vec<&str>)>,
@@ -178,4 +301,53 @@ where
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_reset_is_not_a_spike() {
+        let mut rates = RateTracker::new();
+        let start = Instant::now();
+
+        assert_eq!(rates.rate("eth0:rx", 5_000_000_000, start), None);
+        assert_eq!(rates.rate("eth0:rx", 5_000_001_000, start + Duration::from_secs(1)), Some(1000.0));
+        // 重置后从 0 重新计数，增量只算新读数本身
+        assert_eq!(rates.rate("eth0:rx", 300, start + Duration::from_secs(2)), Some(300.0));
+    }
+
+    #[test]
+    fn u32_counter_wraps() {
+        let last = u32::MAX as u64 - 99;
+        assert_eq!(counter_delta_u32(last, 50), 150);
+        // 回绕后的增量过大时视为重置
+        assert_eq!(counter_delta_u32(1000, 10), 10);
+        // 64 位计数器变小总是重置
+        assert_eq!(counter_delta(last, 50), 50);
+    }
+
+    #[test]
+    fn rapl_counter_wraps_at_its_range() {
+        let mut rates = RateTracker::new();
+        let start = Instant::now();
+        let range = 262_143_328_850;
+
+        rates.rate_wrapping("rapl:package-0", range - 1_000_000, range, start);
+        let rate = rates.rate_wrapping("rapl:package-0", 4_000_000, range, start + Duration::from_secs(2));
+        assert_eq!(rate, Some(2_500_000.0));
+    }
+
+    #[test]
+    fn prunes_stale_counters() {
+        let mut rates = RateTracker::new();
+        let start = Instant::now();
+        rates.rate("docker:gone:rx", 10, start);
+        rates.rate("eth0:rx", 10, start + RATE_MAX_AGE);
+
+        rates.prune(RATE_MAX_AGE, start + RATE_MAX_AGE + Duration::from_secs(1));
+        assert!(!rates.samples.contains_key("docker:gone:rx"));
+        assert!(rates.samples.contains_key("eth0:rx"));
+    }
+}
@@ -1,168 +1,193 @@
 use crate::models::*;
 use crate::collector_utils::*;
 use crate::collector_config::GlobalConfig;
-use crate::docker_parser::parse_docker_containers;
+use crate::collector_tasks::{spawn_collector, Collector, RunMode, SharedSnapshot};
+use crate::realtime_protocol::{RealtimeFrame, RealtimeHistory};
+use crate::docker_parser::{
+    block_io_stat_path, container_id_from_cgroup, parse_block_io_stat, parse_docker_containers, parse_inspect_pids,
+    parse_net_dev_totals,
+};
+use crate::connection_parser::*;
+use crate::net_stats::*;
+use crate::disk_stats::*;
+use crate::smart_parser::*;
+use crate::storage_pool_parser::*;
+use crate::hwmon::*;
+use crate::cpu_stats::*;
+use crate::pressure::collect_pressure;
+use crate::mem_stats::*;
+use crate::gpu::{GpuBackend, default_backends};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
 use chrono::Utc;
-use sysinfo::{System, Networks, Disks, ProcessStatus};
-use tokio::sync::broadcast;
-use tokio::time::{interval, Duration};
+use sysinfo::{System, Networks, Disks, ProcessStatus, RefreshKind, CpuRefreshKind, MemoryRefreshKind};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Duration, Instant};
 use tracing::{info, debug, warn};
 
+/// 每轮采集最多发起的反向 DNS 查询数
+const MAX_DNS_LOOKUPS_PER_TICK: usize = 32;
+
 pub struct SystemCollector {
-    tx: broadcast::Sender<RealtimeData>,
-    config: CollectorConfig,
-    global_config: GlobalConfig,
-    gpu_cache: DataCache<Vec<GpuInfo>>,
+    tx: broadcast::Sender<Arc<RealtimeFrame>>,
+    history: Arc<RealtimeHistory>,
+    /// 当前的监控配置，热加载后各采集器下一次采集即使用新值
+    config_rx: watch::Receiver<GlobalConfig>,
+    gpu_backends: Vec<Box<dyn GpuBackend>>,
     sensors_cache: DataCache<(Option<f32>, Option<f32>)>,
+    dns_cache: DataCache<Option<String>>,
+    interface_addr_cache: DataCache<InterfaceAddresses>,
 }
 
 impl SystemCollector {
-    pub fn new(tx: broadcast::Sender<RealtimeData>, global_config: GlobalConfig) -> Self {
-        let config = CollectorConfig::from(global_config.clone());
+    pub fn new(
+        tx: broadcast::Sender<Arc<RealtimeFrame>>,
+        history: Arc<RealtimeHistory>,
+        config_rx: watch::Receiver<GlobalConfig>,
+    ) -> Self {
+        let config = CollectorConfig::from(&*config_rx.borrow());
         Self {
             tx,
-            gpu_cache: DataCache::new(config.gpu_cache_ttl),
+            history,
+            gpu_backends: default_backends(),
             sensors_cache: DataCache::new(config.sensors_cache_ttl),
-            config,
-            global_config,
+            dns_cache: DataCache::new(config.dns_cache_ttl),
+            interface_addr_cache: DataCache::new(config.interface_addr_cache_ttl),
+            config_rx,
         }
     }
 
+    /// 当前配置对应的采集参数
+    fn config(&self) -> CollectorConfig {
+        CollectorConfig::from(&*self.config_rx.borrow())
+    }
+
+    /// 为每个采集器启动独立任务，并按 collect_interval_secs 推送最新快照
+    ///
+    /// 监控配置变化时各采集器自行判断是否需要按新的调度重新计时，未受影响的采集器继续运行，
+    /// 速率基线、GPU 后端和缓存都保留；快照和广播通道保持不变，已连接的客户端不受影响。
     pub async fn start(self) {
-        let mut sys = System::new_all();
-        let mut networks = Networks::new_with_refreshed_list();
-        let mut disks = Disks::new_with_refreshed_list();
-        let mut interval = interval(Duration::from_secs(self.global_config.collectors.collect_interval_secs));
-        
-        // 记录上一次的网络数据
-        let mut last_rx_bytes = 0u64;
-        let mut last_tx_bytes = 0u64;
-        let mut first_run = true;
+        info!("System collector started with config: {:?}", self.config());
 
-        info!("System collector started with config: {:?}", self.config);
+        let tx = self.tx.clone();
+        let history = self.history.clone();
+        let mut config_rx = self.config_rx.clone();
+        let snapshot = SharedSnapshot::default();
+        let system = Arc::new(self);
+        let _handles = spawn_collectors(&system, &snapshot, RunMode::Scheduled).await;
+
+        // 首次推送等各采集器跑完第一轮
+        let mut period = push_period(&config_rx.borrow_and_update());
+        let mut interval = interval_at(Instant::now() + period, period);
 
         loop {
-            interval.tick().await;
-            
-            // 顺序刷新系统信息以避免借用冲突
-            let refresh_start = std::time::Instant::now();
-            sys.refresh_cpu_usage();
-            sys.refresh_memory();
-            sys.refresh_processes();
-            networks.refresh();
-            disks.refresh();
-            debug!("System refresh took: {:?}", refresh_start.elapsed());
-            
-            // 收集CPU信息
-            let cpu_info = self.collect_cpu_info(&sys).await;
-            
-            // 收集内存信息
-            let memory_info = collect_memory_info(&sys);
-            
-            // 收集磁盘信息
-            let disk_list = collect_disk_info(&disks);
-            
-            // 收集网络信息
-            let (network_info, new_rx, new_tx) = collect_network_info(
-                &networks,
-                last_rx_bytes,
-                last_tx_bytes,
-                first_run
-            );
-            last_rx_bytes = new_rx;
-            last_tx_bytes = new_tx;
-            if first_run {
-                first_run = false;
-            }
-            
-            // 获取系统负载
-            let load_average = LoadAverage {
-                one: sysinfo::System::load_average().one,
-                five: sysinfo::System::load_average().five,
-                fifteen: sysinfo::System::load_average().fifteen,
-            };
-            
-            // 并行收集外部命令数据（根据配置启用）
-            let (gpu_info, port_info, process_info) = tokio::join!(
-                async {
-                    if self.global_config.collectors.enable_gpu {
-                        self.collect_gpu_info_cached().await
-                    } else {
-                        None
-                    }
-                },
-                async {
-                    if self.global_config.collectors.enable_ports {
-                        self.collect_port_info().await
-                    } else {
-                        Vec::new()
+            tokio::select! {
+                _ = interval.tick() => {
+                    let mut realtime_data = snapshot.read().await.clone();
+                    realtime_data.timestamp = Utc::now().timestamp();
+                    link_snapshot(&mut realtime_data);
+
+                    // 记录到历史缓冲区后发送
+                    if let Err(e) = tx.send(history.record(realtime_data)) {
+                        debug!("No receivers for realtime data: {}", e);
                     }
-                },
-                async {
-                    if self.global_config.collectors.enable_processes {
-                        collect_process_info(&sys, self.config.max_processes)
-                    } else {
-                        Vec::new()
+                }
+                Ok(()) = config_rx.changed() => {
+                    let global_config = config_rx.borrow_and_update().clone();
+                    info!("Monitoring configuration changed");
+                    warn_unknown_schedules(&global_config);
+
+                    let config = CollectorConfig::from(&global_config);
+                    system.sensors_cache.set_ttl(config.sensors_cache_ttl);
+                    system.dns_cache.set_ttl(config.dns_cache_ttl);
+                    system.interface_addr_cache.set_ttl(config.interface_addr_cache_ttl);
+
+                    if push_period(&global_config) != period {
+                        period = push_period(&global_config);
+                        interval = interval_at(Instant::now() + period, period);
                     }
                 }
-            );
-            
-            // 收集Docker容器信息
-            let docker_containers = if self.global_config.collectors.enable_docker {
-                self.collect_docker_containers().await
-            } else {
-                Vec::new()
-            };
-            
-            // 创建实时数据
-            let realtime_data = RealtimeData {
-                timestamp: Utc::now().timestamp(),
-                cpu: cpu_info,
-                memory: memory_info,
-                disks: disk_list,
-                network: network_info,
-                load_average,
-                uptime_secs: System::uptime(),
-                gpu: gpu_info,
-                ports: port_info,
-                processes: process_info,
-                docker_containers,
-            };
-            
-            // 发送数据
-            if let Err(e) = self.tx.send(realtime_data) {
-                debug!("No receivers for realtime data: {}", e);
             }
         }
     }
 
-    async fn collect_cpu_info(&self, sys: &System) -> CpuInfo {
-        let total_usage = sys.global_cpu_info().cpu_usage();
-        let core_usage: Vec<f32> = sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
-        
-        // 获取 CPU 温度和功耗（带缓存，根据配置启用）
-        let (temperature_celsius, power_watts) = if self.global_config.collectors.enable_sensors {
-            if let Some(cached) = self.sensors_cache.get("cpu").await {
-                cached
-            } else {
-                let sensors = self.collect_cpu_sensors().await;
-                self.sensors_cache.set("cpu".to_string(), sensors).await;
-                sensors
+    /// 所有启用的采集器各运行一轮，返回汇总后的数据（snapshot 子命令）
+    ///
+    /// 速率类数据（CPU 使用率、网络和磁盘吞吐）需要两次采样，因此至少耗时 `sample`。
+    pub async fn snapshot(global_config: GlobalConfig, sample: Duration) -> RealtimeData {
+        let (tx, _rx) = broadcast::channel(1);
+        let (_config_tx, config_rx) = watch::channel(global_config);
+        let system = Arc::new(SystemCollector::new(tx, Arc::new(RealtimeHistory::new(1)), config_rx));
+        let snapshot = SharedSnapshot::default();
+
+        for handle in spawn_collectors(&system, &snapshot, RunMode::Once { sample }).await {
+            if let Err(e) = handle.await {
+                warn!("Collector task failed: {}", e);
             }
-        } else {
-            (None, None)
-        };
-        
-        CpuInfo {
-            total_usage,
-            core_usage,
-            temperature_celsius,
-            power_watts,
         }
+
+        let mut realtime_data = snapshot.read().await.clone();
+        realtime_data.timestamp = Utc::now().timestamp();
+        link_snapshot(&mut realtime_data);
+        realtime_data
+    }
+
+    /// 从 hwmon/thermal 温度和 RAPL 能量计数器计算 CPU 温度与功耗，读不到时降级到 sensors 命令
+    async fn collect_cpu_sensors_native(
+        &self,
+        sensors: &[SensorChip],
+        rates: &mut RateTracker,
+    ) -> (Vec<TemperatureSensor>, Option<f32>, Option<f32>) {
+        let sys_root = Path::new("/sys");
+
+        let mut temperatures = cpu_temperatures(sensors);
+        // 部分 ARM 板卡只有 thermal zone
+        if temperatures.is_empty() {
+            temperatures = read_thermal_zones(sys_root)
+                .into_iter()
+                .filter(|t| is_cpu_sensor_source(&t.source))
+                .collect();
+        }
+        let mut temperature = temperatures.iter().map(|t| t.celsius).reduce(f32::max);
+
+        // 功耗 = 各 package 能量计数器的增量 / 实际经过的时间
+        let now = std::time::Instant::now();
+        let packages = read_rapl_packages(sys_root);
+        let mut power = None;
+        for package in &packages {
+            let watts = rates
+                .rate_wrapping(&format!("rapl:{}", package.zone), package.energy_uj, package.max_energy_range_uj, now)
+                .map(|uj_per_sec| (uj_per_sec / 1_000_000.0) as f32);
+            power = match (power, watts) {
+                (Some(total), Some(w)) => Some(total + w),
+                (None, w) => w,
+                (total, None) => total,
+            };
+        }
+
+        if (temperature.is_none() || (power.is_none() && packages.is_empty())) && self.config().enable_fallback {
+            let (fallback_temperature, fallback_power) = match self.sensors_cache.get("cpu").await {
+                Some(cached) => cached,
+                None => {
+                    let sensors = self.collect_cpu_sensors().await;
+                    self.sensors_cache.set("cpu".to_string(), sensors).await;
+                    sensors
+                }
+            };
+            temperature = temperature.or(fallback_temperature);
+            if packages.is_empty() {
+                power = fallback_power;
+            }
+        }
+
+        (temperatures, temperature, power)
     }
 
     async fn collect_cpu_sensors(&self) -> (Option<f32>, Option<f32>) {
-        match execute_command_with_timeout("sensors", &[], self.config.command_timeout).await {
+        match execute_command_with_timeout("sensors", &[], self.config().command_timeout).await {
             Ok(output) => parse_sensors_output(&output),
             Err(e) => {
                 debug!("Failed to get CPU sensors: {}", e);
@@ -171,34 +196,21 @@ impl SystemCollector {
         }
     }
 
-    async fn collect_gpu_info_cached(&self) -> Option<Vec<GpuInfo>> {
-        // 先检查缓存
-        if let Some(cached) = self.gpu_cache.get("nvidia").await {
-            return Some(cached);
-        }
+    async fn collect_gpu_info(&self) -> Option<Vec<GpuInfo>> {
+        let mut gpus = Vec::new();
 
-        // 采集新数据
-        match self.collect_gpu_info().await {
-            Some(data) => {
-                self.gpu_cache.set("nvidia".to_string(), data.clone()).await;
-                Some(data)
+        // 逐个后端采集，未安装 nvidia-smi 等情况不影响其它厂商
+        for backend in &self.gpu_backends {
+            match backend.collect(self.config().command_timeout).await {
+                Some(data) => gpus.extend(data),
+                None => debug!("No {} GPUs found", backend.vendor()),
             }
-            None => None,
         }
-    }
-
-    async fn collect_gpu_info(&self) -> Option<Vec<GpuInfo>> {
-        let args = vec![
-            "--query-gpu=gpu_name,index,memory.total,memory.used,memory.free,utilization.gpu,temperature.gpu,power.draw,power.limit,fan.speed,clocks.gr,clocks.mem",
-            "--format=csv,noheader,nounits"
-        ];
 
-        match execute_command_with_timeout("nvidia-smi", &args, self.config.command_timeout).await {
-            Ok(output) => parse_nvidia_smi_output(&output),
-            Err(e) => {
-                debug!("nvidia-smi failed: {}", e);
-                None
-            }
+        if gpus.is_empty() {
+            None
+        } else {
+            Some(gpus)
         }
     }
 
@@ -224,7 +236,7 @@ impl SystemCollector {
         let output = execute_command_with_timeout(
             "ss",
             &["-tulpn", "-H"],
-            self.config.port_scan_timeout
+            self.config().port_scan_timeout
         ).await?;
         
         Ok(parse_ss_output(&output))
@@ -234,15 +246,357 @@ impl SystemCollector {
         let output = execute_command_with_timeout(
             "netstat",
             &["-tulpn"],
-            self.config.port_scan_timeout
+            self.config().port_scan_timeout
         ).await?;
         
         Ok(parse_netstat_output(&output))
     }
+
+    async fn collect_network_info(
+        &self,
+        networks: &mut Networks,
+        rates: &mut RateTracker,
+    ) -> NetworkInfo {
+        let mut snapshots = read_sysfs_interfaces(Path::new("/sys"));
+        let now = std::time::Instant::now();
+        if snapshots.is_empty() && self.config().enable_fallback {
+            // 没有 sysfs 时降级到 sysinfo，只能拿到字节、包和错误计数
+            networks.refresh();
+            snapshots = snapshots_from_sysinfo(networks);
+        }
+
+        let addresses = self.collect_interface_addresses().await;
+        let exclude_virtual = self.config_rx.borrow().collectors.exclude_virtual_interfaces;
+
+        let mut rx_speed_kbps = 0.0;
+        let mut tx_speed_kbps = 0.0;
+        let mut total_rx_bytes = 0u64;
+        let mut total_tx_bytes = 0u64;
+
+        let interfaces: Vec<NetworkInterface> = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                let counters = snapshot.counters;
+                // 首次出现的网卡没有历史数据，速率记为 0
+                let mut rate = |counter: &str, value: u64| {
+                    rates.rate(&format!("net:{}:{}", snapshot.name, counter), value, now).unwrap_or(0.0)
+                };
+
+                let rx_kbps = rate("rx_bytes", counters.rx_bytes) / 1024.0;
+                let tx_kbps = rate("tx_bytes", counters.tx_bytes) / 1024.0;
+                let rx_packets_per_sec = rate("rx_packets", counters.rx_packets);
+                let tx_packets_per_sec = rate("tx_packets", counters.tx_packets);
+
+                if !(exclude_virtual && snapshot.is_virtual) {
+                    rx_speed_kbps += rx_kbps;
+                    tx_speed_kbps += tx_kbps;
+                    total_rx_bytes += counters.rx_bytes;
+                    total_tx_bytes += counters.tx_bytes;
+                }
+
+                let (ipv4, ipv6) = addresses.get(&snapshot.name).cloned().unwrap_or_default();
+
+                NetworkInterface {
+                    ipv4,
+                    ipv6,
+                    mac_address: snapshot.mac_address,
+                    mtu: snapshot.mtu,
+                    is_up: snapshot.is_up,
+                    operstate: snapshot.operstate,
+                    speed_mbps: snapshot.speed_mbps,
+                    is_virtual: snapshot.is_virtual,
+                    rx_speed_kbps: rx_kbps,
+                    tx_speed_kbps: tx_kbps,
+                    rx_packets_per_sec,
+                    tx_packets_per_sec,
+                    rx_errors: counters.rx_errors,
+                    tx_errors: counters.tx_errors,
+                    rx_dropped: counters.rx_dropped,
+                    tx_dropped: counters.tx_dropped,
+                    total_rx_bytes: counters.rx_bytes,
+                    total_tx_bytes: counters.tx_bytes,
+                    name: snapshot.name,
+                }
+            })
+            .collect();
+
+        NetworkInfo {
+            interfaces,
+            rx_speed_kbps,
+            tx_speed_kbps,
+            total_rx_gb: total_rx_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+            total_tx_gb: total_tx_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
+        }
+    }
+
+    /// 各磁盘的 SMART 数据，按 smartctl 设备路径索引；休眠的磁盘沿用 `last` 中的读数
+    async fn collect_smart_info(&self, last: &HashMap<String, SmartInfo>) -> Vec<(String, SmartInfo)> {
+        let devices = match execute_command_with_timeout(
+            "smartctl",
+            &["--scan", "--json"],
+            self.config().command_timeout
+        ).await {
+            Ok(output) => parse_smartctl_scan(&output),
+            Err(e) => {
+                debug!("smartctl --scan failed: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let standby = &smartctl_standby_arg();
+        let queries = devices.iter().map(|(device, device_type)| async move {
+            // smartctl 的退出码是位掩码，磁盘有问题时也会非零，因此需要原始输出；
+            // -n standby 使休眠的磁盘不被唤醒
+            match execute_command_output_with_timeout(
+                "smartctl",
+                &["-n", standby, "-a", "--json", "-d", device_type, device],
+                self.config().command_timeout
+            ).await {
+                Ok(output) if is_smartctl_standby(output.exit_code) => {
+                    debug!("{} is in standby, keeping the last SMART reading", device);
+                    Some((device.clone(), standby_smart_info(device, last.get(device))))
+                }
+                Ok(output) if !is_smartctl_fatal(output.exit_code) => {
+                    parse_smartctl_device(&output.stdout, output.exit_code).map(|info| (device.clone(), info))
+                }
+                Ok(output) => {
+                    debug!("smartctl failed for {}: exit code {:?}", device, output.exit_code);
+                    None
+                }
+                Err(e) => {
+                    debug!("smartctl failed for {}: {}", device, e);
+                    None
+                }
+            }
+        });
+
+        futures::future::join_all(queries).await.into_iter().flatten().collect()
+    }
+
+    async fn collect_storage_pools(&self, disk_list: &[DiskInfo]) -> Vec<StoragePoolInfo> {
+        let (mut pools, zfs_pools, btrfs_pools) = tokio::join!(
+            async {
+                match tokio::fs::read_to_string("/proc/mdstat").await {
+                    Ok(content) => parse_mdstat(&content),
+                    Err(_) => Vec::new(),
+                }
+            },
+            self.collect_zfs_pools(),
+            self.collect_btrfs_pools(disk_list)
+        );
+        pools.extend(zfs_pools);
+        pools.extend(btrfs_pools);
+        pools
+    }
+
+    async fn collect_zfs_pools(&self) -> Vec<StoragePoolInfo> {
+        let status = match execute_command_with_timeout(
+            "zpool",
+            &["status", "-p"],
+            self.config().command_timeout
+        ).await {
+            Ok(output) => output,
+            Err(e) => {
+                debug!("zpool status failed: {}", e);
+                return Vec::new();
+            }
+        };
+        let mut pools = parse_zpool_status(&status);
+
+        // 容量来自 zpool list
+        if let Ok(output) = execute_command_with_timeout(
+            "zpool",
+            &["list", "-Hp", "-o", "name,size,alloc,free,health"],
+            self.config().command_timeout
+        ).await {
+            let capacities = parse_zpool_list(&output);
+            for pool in &mut pools {
+                if let Some((size, alloc, free, _)) = capacities.get(&pool.name) {
+                    pool.size_bytes = Some(*size);
+                    pool.allocated_bytes = Some(*alloc);
+                    pool.free_bytes = Some(*free);
+                }
+            }
+        }
+
+        pools
+    }
+
+    async fn collect_btrfs_pools(&self, disk_list: &[DiskInfo]) -> Vec<StoragePoolInfo> {
+        let mut pools: Vec<StoragePoolInfo> = Vec::new();
+
+        for disk in disk_list.iter().filter(|d| d.file_system == "btrfs") {
+            let output = match execute_command_with_timeout(
+                "btrfs",
+                &["device", "stats", &disk.mount_point],
+                self.config().command_timeout
+            ).await {
+                Ok(output) => output,
+                Err(e) => {
+                    debug!("btrfs device stats failed for {}: {}", disk.mount_point, e);
+                    continue;
+                }
+            };
+            let Some(mut pool) = parse_btrfs_device_stats(&disk.mount_point, &output) else {
+                continue;
+            };
+
+            // 同一文件系统的多个子卷挂载只报告一次
+            if pools.iter().any(|p| p.members.iter().map(|m| &m.name).eq(pool.members.iter().map(|m| &m.name))) {
+                continue;
+            }
+
+            pool.size_bytes = Some((disk.total_gb * 1024.0 * 1024.0 * 1024.0) as u64);
+            pool.allocated_bytes = Some((disk.used_gb * 1024.0 * 1024.0 * 1024.0) as u64);
+            pool.free_bytes = Some((disk.free_gb * 1024.0 * 1024.0 * 1024.0) as u64);
+            if let Ok(output) = execute_command_with_timeout(
+                "btrfs",
+                &["scrub", "status", &disk.mount_point],
+                self.config().command_timeout
+            ).await {
+                pool.operation = parse_btrfs_scrub_status(&output);
+            }
+
+            pools.push(pool);
+        }
+
+        pools
+    }
+
+    async fn collect_interface_addresses(&self) -> InterfaceAddresses {
+        if let Some(cached) = self.interface_addr_cache.get("ip").await {
+            return cached;
+        }
+
+        match execute_command_with_timeout("ip", &["-j", "addr", "show"], self.config().command_timeout).await {
+            Ok(output) => {
+                let addresses = parse_ip_addr_json(&output);
+                self.interface_addr_cache.set("ip".to_string(), addresses.clone()).await;
+                addresses
+            }
+            Err(e) => {
+                debug!("Failed to get interface addresses: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    async fn collect_connection_info(&self) -> ConnectionsInfo {
+        // 尝试使用 ss 命令，失败时降级到 netstat
+        let connections = match execute_command_with_timeout(
+            "ss",
+            &["-tnp", "-H"],
+            self.config().port_scan_timeout
+        ).await {
+            Ok(output) => parse_ss_connections(&output),
+            Err(e) => {
+                debug!("ss command failed: {}, trying netstat", e);
+                match execute_command_with_timeout(
+                    "netstat",
+                    &["-tnp"],
+                    self.config().port_scan_timeout
+                ).await {
+                    Ok(output) => parse_netstat_connections(&output),
+                    Err(e) => {
+                        warn!("Both ss and netstat failed: {}", e);
+                        Vec::new()
+                    }
+                }
+            }
+        };
+
+        let connections = self.resolve_remote_hostnames(connections).await;
+        aggregate_connections(connections, self.config().max_connections)
+    }
+
+    async fn resolve_remote_hostnames(&self, mut connections: Vec<ConnectionInfo>) -> Vec<ConnectionInfo> {
+        let mut addresses: Vec<String> = connections.iter()
+            .map(|c| c.remote_address.clone())
+            .filter(|a| is_resolvable_address(a))
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+
+        // 命中缓存的直接使用，其余的并发解析（解析失败也会缓存，避免反复查询）
+        let mut resolved = std::collections::HashMap::new();
+        let mut pending = Vec::new();
+        for address in addresses {
+            match self.dns_cache.get(&address).await {
+                Some(hostname) => {
+                    resolved.insert(address, hostname);
+                }
+                None => pending.push(address),
+            }
+        }
+        // 限制每轮的解析数量，剩余的留到下一轮
+        pending.truncate(MAX_DNS_LOOKUPS_PER_TICK);
+
+        let lookups = pending.into_iter().map(|address| async move {
+            let hostname = execute_command_with_timeout(
+                "getent",
+                &["hosts", &address],
+                Duration::from_secs(1)
+            ).await
+                .ok()
+                .and_then(|output| parse_getent_hosts(&output));
+            (address, hostname)
+        });
+        for (address, hostname) in futures::future::join_all(lookups).await {
+            self.dns_cache.set(address.clone(), hostname.clone()).await;
+            resolved.insert(address, hostname);
+        }
+
+        for conn in &mut connections {
+            conn.remote_hostname = resolved.get(&conn.remote_address).cloned().flatten();
+        }
+        connections
+    }
     
-    async fn collect_docker_containers(&self) -> Vec<DockerContainer> {
+    async fn collect_docker_containers(&self, rates: &mut RateTracker) -> Vec<DockerContainer> {
         match self.collect_docker_containers_internal().await {
-            Ok(containers) => containers,
+            Ok(mut containers) => {
+                let pids = self.collect_docker_pids(&containers).await;
+                let now = std::time::Instant::now();
+                for container in &mut containers {
+                    let pid = pids.iter()
+                        .find(|(full_id, _)| full_id.starts_with(&container.id))
+                        .map(|(_, pid)| *pid);
+                    // 键中带上数据来源，来源切换时从新的基线开始，不拿不同精度的读数相减
+                    let mut rate = |source: &str, counter: &str, value: u64| {
+                        rates.rate(&format!("docker:{}:{}:{}", container.id, source, counter), value, now).unwrap_or(0.0) / 1024.0
+                    };
+
+                    // 优先使用容器网络命名空间中的字节计数，docker stats 的 NetIO 只有约三位有效数字
+                    let net_bytes = pid.and_then(|pid| {
+                        parse_net_dev_totals(&std::fs::read_to_string(format!("/proc/{}/net/dev", pid)).ok()?)
+                    });
+                    let (source, rx_bytes, tx_bytes) = match net_bytes {
+                        Some((rx_bytes, tx_bytes)) => {
+                            container.network_rx_mb = rx_bytes as f64 / 1024.0 / 1024.0;
+                            container.network_tx_mb = tx_bytes as f64 / 1024.0 / 1024.0;
+                            ("procfs", rx_bytes, tx_bytes)
+                        }
+                        None => (
+                            "stats",
+                            (container.network_rx_mb * 1024.0 * 1024.0) as u64,
+                            (container.network_tx_mb * 1024.0 * 1024.0) as u64,
+                        ),
+                    };
+                    container.network_rx_kbps = rate(source, "rx_bytes", rx_bytes);
+                    container.network_tx_kbps = rate(source, "tx_bytes", tx_bytes);
+
+                    let block_bytes = pid.and_then(|pid| {
+                        let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+                        let stat = std::fs::read_to_string(block_io_stat_path(&cgroup)?).ok()?;
+                        Some(parse_block_io_stat(&stat))
+                    });
+                    if let Some((read_bytes, write_bytes)) = block_bytes {
+                        container.block_read_kbps = rate("cgroup", "read_bytes", read_bytes);
+                        container.block_write_kbps = rate("cgroup", "write_bytes", write_bytes);
+                    }
+                }
+                containers
+            }
             Err(e) => {
                 debug!("Failed to collect Docker containers: {}", e);
                 Vec::new()
@@ -250,6 +604,27 @@ impl SystemCollector {
         }
     }
     
+    /// 运行中容器的 (完整 ID, 主进程 PID)，用于读取其网络命名空间和 cgroup 中的计数
+    async fn collect_docker_pids(&self, containers: &[DockerContainer]) -> Vec<(String, u32)> {
+        let ids: Vec<&str> = containers.iter()
+            .filter(|container| container.state.running)
+            .map(|container| container.id.as_str())
+            .collect();
+        if ids.is_empty() {
+            return Vec::new();
+        }
+
+        let mut args = vec!["inspect", "--format", "{{.Id}} {{.State.Pid}}"];
+        args.extend(ids);
+        match execute_command_with_timeout("docker", &args, self.config().command_timeout).await {
+            Ok(output) => parse_inspect_pids(&output),
+            Err(e) => {
+                debug!("docker inspect failed: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     async fn collect_docker_containers_internal(&self) -> Result<Vec<DockerContainer>, String> {
         // 首先检查Docker是否可用
         if let Err(_) = execute_command_with_timeout("docker", &["version"], Duration::from_secs(2)).await {
@@ -260,14 +635,14 @@ impl SystemCollector {
         let containers_json = execute_command_with_timeout(
             "docker",
             &["ps", "-a", "--format", "{{json .}}"],
-            self.config.command_timeout
+            self.config().command_timeout
         ).await?;
         
         // 获取容器统计信息
         let stats_output = execute_command_with_timeout(
             "docker",
             &["stats", "--no-stream", "--format", "{{json .}}"],
-            self.config.command_timeout
+            self.config().command_timeout
         ).await?;
         
         // 解析容器信息
@@ -275,6 +650,594 @@ impl SystemCollector {
     }
 }
 
+/// 推送快照的间隔
+fn push_period(config: &GlobalConfig) -> Duration {
+    Duration::from_secs(config.collectors.collect_interval_secs.max(1))
+}
+
+fn warn_unknown_schedules(config: &GlobalConfig) {
+    for name in config.collectors.schedules.keys() {
+        if !COLLECTOR_NAMES.contains(&name.as_str()) {
+            warn!("Unknown collector in schedules: {}", name);
+        }
+    }
+}
+
+/// 启动所有采集器任务，各任务通过 watch 通道跟随配置变化
+async fn spawn_collectors(
+    system: &Arc<SystemCollector>,
+    snapshot: &SharedSnapshot,
+    mode: RunMode,
+) -> Vec<JoinHandle<()>> {
+    warn_unknown_schedules(&system.config_rx.borrow());
+    let config = &system.config_rx;
+
+    let handles = [
+        spawn_collector(CpuCollector::new(), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(MemoryCollector::new(), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(DisksCollector::new(), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(DiskIoCollector::new(), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(SmartCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(StoragePoolsCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(NetworkCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(PressureCollector::new(), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(GpuCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(PortsCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(ConnectionsCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(ProcessesCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(DockerCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+        spawn_collector(SensorsCollector::new(system.clone()), config.clone(), snapshot.clone(), mode).await,
+    ];
+    handles.into_iter().flatten().collect()
+}
+
+/// 所有采集器名称，用于校验 schedules 配置
+const COLLECTOR_NAMES: &[&str] = &[
+    "cpu", "memory", "disks", "disk_io", "smart", "storage_pools", "network", "pressure",
+    "gpu", "ports", "connections", "processes", "docker", "sensors",
+];
+
+
+/// CPU 使用率、频率、时间占比，以及负载和运行时间
+struct CpuCollector {
+    sys: System,
+    rates: RateTracker,
+    // /proc/stat 的 CPU 时间需要按比例计算，单独保存上一次采样
+    last_cpu_times: Option<CpuTimes>,
+}
+
+impl CpuCollector {
+    fn new() -> Self {
+        Self {
+            sys: System::new_with_specifics(RefreshKind::new().with_cpu(CpuRefreshKind::everything())),
+            rates: RateTracker::new(),
+            last_cpu_times: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for CpuCollector {
+    type Output = (CpuInfo, LoadAverage, u64);
+
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Self::Output {
+        self.sys.refresh_cpu_usage();
+        let total_usage = self.sys.global_cpu_info().cpu_usage();
+        let core_usage: Vec<f32> = self.sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect();
+        let (core_frequency_mhz, governor) = read_cpu_frequencies(Path::new("/sys"), Path::new("/proc"));
+
+        // 各状态时间占比，以及上下文切换、中断速率
+        let mut time_breakdown = None;
+        let mut context_switches_per_sec = None;
+        let mut interrupts_per_sec = None;
+        if let Ok(content) = tokio::fs::read_to_string("/proc/stat").await {
+            let stat = parse_proc_stat(&content);
+            let now = std::time::Instant::now();
+            time_breakdown = self.last_cpu_times.and_then(|last| stat.cpu.breakdown_since(&last));
+            self.last_cpu_times = Some(stat.cpu);
+            context_switches_per_sec = self.rates.rate("cpu:ctxt", stat.context_switches, now);
+            interrupts_per_sec = self.rates.rate("cpu:intr", stat.interrupts, now);
+        }
+
+        let load = System::load_average();
+        let cpu = CpuInfo {
+            total_usage,
+            core_usage,
+            core_frequency_mhz,
+            governor,
+            time_breakdown,
+            context_switches_per_sec,
+            interrupts_per_sec,
+            ..CpuInfo::default()
+        };
+        (cpu, LoadAverage { one: load.one, five: load.five, fifteen: load.fifteen }, System::uptime())
+    }
+
+    fn apply((cpu, load_average, uptime_secs): Self::Output, data: &mut RealtimeData) {
+        // 温度和功耗由 sensors 采集器写入，这里保留
+        let previous = std::mem::take(&mut data.cpu);
+        data.cpu = CpuInfo {
+            temperature_celsius: previous.temperature_celsius,
+            power_watts: previous.power_watts,
+            temperatures: previous.temperatures,
+            ..cpu
+        };
+        data.load_average = load_average;
+        data.uptime_secs = uptime_secs;
+    }
+}
+
+/// 内存、交换分区、meminfo 明细和换页速率
+struct MemoryCollector {
+    sys: System,
+    rates: RateTracker,
+}
+
+impl MemoryCollector {
+    fn new() -> Self {
+        Self {
+            sys: System::new_with_specifics(RefreshKind::new().with_memory(MemoryRefreshKind::everything())),
+            rates: RateTracker::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for MemoryCollector {
+    type Output = MemoryInfo;
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> MemoryInfo {
+        self.sys.refresh_memory();
+        collect_memory_info(&self.sys, &mut self.rates)
+    }
+
+    fn apply(memory: MemoryInfo, data: &mut RealtimeData) {
+        data.memory = memory;
+    }
+}
+
+/// 挂载点容量
+struct DisksCollector {
+    disks: Disks,
+}
+
+impl DisksCollector {
+    fn new() -> Self {
+        Self { disks: Disks::new_with_refreshed_list() }
+    }
+}
+
+#[async_trait]
+impl Collector for DisksCollector {
+    type Output = Vec<DiskInfo>;
+
+    fn name(&self) -> &'static str {
+        "disks"
+    }
+
+    // 容量变化缓慢，默认不低于 5 秒
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.collectors.collect_interval_secs.max(5))
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Vec<DiskInfo> {
+        self.disks.refresh_list();
+        collect_disk_info(&self.disks)
+    }
+
+    fn apply(disks: Vec<DiskInfo>, data: &mut RealtimeData) {
+        data.disks = disks;
+    }
+}
+
+/// 块设备 I/O 速率，挂载点来自 disks 采集器的结果
+struct DiskIoCollector {
+    rates: RateTracker,
+}
+
+impl DiskIoCollector {
+    fn new() -> Self {
+        Self { rates: RateTracker::new() }
+    }
+}
+
+#[async_trait]
+impl Collector for DiskIoCollector {
+    type Output = Vec<DiskIoInfo>;
+
+    fn name(&self) -> &'static str {
+        "disk_io"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_disk_io
+    }
+
+    async fn collect(&mut self, latest: &SharedSnapshot) -> Vec<DiskIoInfo> {
+        let disk_list = latest.read().await.disks.clone();
+        let disk_io = collect_disk_io(&disk_list, &mut self.rates).await;
+        self.rates.prune(RATE_MAX_AGE, std::time::Instant::now());
+        disk_io
+    }
+
+    fn apply(disk_io: Vec<DiskIoInfo>, data: &mut RealtimeData) {
+        data.disk_io = disk_io;
+    }
+}
+
+/// smartctl 磁盘健康状态
+struct SmartCollector {
+    system: Arc<SystemCollector>,
+    /// 上一次的读数，磁盘休眠时沿用
+    last: HashMap<String, SmartInfo>,
+}
+
+impl SmartCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system, last: HashMap::new() }
+    }
+}
+
+#[async_trait]
+impl Collector for SmartCollector {
+    type Output = Vec<SmartInfo>;
+
+    fn name(&self) -> &'static str {
+        "smart"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_smart
+    }
+
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.performance.smart_cache_ttl_secs)
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Vec<SmartInfo> {
+        let readings = self.system.collect_smart_info(&self.last).await;
+        let smart = readings.iter().map(|(_, info)| info.clone()).collect();
+        self.last = readings.into_iter().collect();
+        smart
+    }
+
+    fn apply(smart: Vec<SmartInfo>, data: &mut RealtimeData) {
+        data.smart = smart;
+    }
+}
+
+/// RAID / ZFS / Btrfs 存储池，Btrfs 挂载点来自 disks 采集器的结果
+struct StoragePoolsCollector {
+    system: Arc<SystemCollector>,
+}
+
+impl StoragePoolsCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system }
+    }
+}
+
+#[async_trait]
+impl Collector for StoragePoolsCollector {
+    type Output = Vec<StoragePoolInfo>;
+
+    fn name(&self) -> &'static str {
+        "storage_pools"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_storage_pools
+    }
+
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.performance.storage_pools_cache_ttl_secs)
+    }
+
+    async fn collect(&mut self, latest: &SharedSnapshot) -> Vec<StoragePoolInfo> {
+        let disk_list = latest.read().await.disks.clone();
+        self.system.collect_storage_pools(&disk_list).await
+    }
+
+    fn apply(storage_pools: Vec<StoragePoolInfo>, data: &mut RealtimeData) {
+        data.storage_pools = storage_pools;
+    }
+}
+
+/// 网卡计数器、速率和地址
+struct NetworkCollector {
+    system: Arc<SystemCollector>,
+    networks: Networks,
+    rates: RateTracker,
+}
+
+impl NetworkCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self {
+            system,
+            networks: Networks::new_with_refreshed_list(),
+            rates: RateTracker::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Collector for NetworkCollector {
+    type Output = NetworkInfo;
+
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> NetworkInfo {
+        let network = self.system.collect_network_info(&mut self.networks, &mut self.rates).await;
+        self.rates.prune(RATE_MAX_AGE, std::time::Instant::now());
+        network
+    }
+
+    fn apply(network: NetworkInfo, data: &mut RealtimeData) {
+        data.network = network;
+    }
+}
+
+/// 压力停顿信息（PSI）
+struct PressureCollector {
+    rates: RateTracker,
+}
+
+impl PressureCollector {
+    fn new() -> Self {
+        Self { rates: RateTracker::new() }
+    }
+}
+
+#[async_trait]
+impl Collector for PressureCollector {
+    type Output = Option<PressureInfo>;
+
+    fn name(&self) -> &'static str {
+        "pressure"
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Option<PressureInfo> {
+        collect_pressure(Path::new("/proc"), &mut self.rates).await
+    }
+
+    fn apply(pressure: Option<PressureInfo>, data: &mut RealtimeData) {
+        data.pressure = pressure;
+    }
+}
+
+/// 各厂商 GPU 后端
+struct GpuCollector {
+    system: Arc<SystemCollector>,
+}
+
+impl GpuCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system }
+    }
+}
+
+#[async_trait]
+impl Collector for GpuCollector {
+    type Output = Option<Vec<GpuInfo>>;
+
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_gpu
+    }
+
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.performance.gpu_cache_ttl_secs)
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Option<Vec<GpuInfo>> {
+        self.system.collect_gpu_info().await
+    }
+
+    fn apply(gpu: Option<Vec<GpuInfo>>, data: &mut RealtimeData) {
+        data.gpu = gpu;
+    }
+}
+
+/// 监听端口
+struct PortsCollector {
+    system: Arc<SystemCollector>,
+}
+
+impl PortsCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system }
+    }
+}
+
+#[async_trait]
+impl Collector for PortsCollector {
+    type Output = Vec<PortInfo>;
+
+    fn name(&self) -> &'static str {
+        "ports"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_ports
+    }
+
+    // 监听端口很少变化，默认不低于 10 秒
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.collectors.collect_interval_secs.max(10))
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Vec<PortInfo> {
+        self.system.collect_port_info().await
+    }
+
+    fn apply(ports: Vec<PortInfo>, data: &mut RealtimeData) {
+        data.ports = ports;
+    }
+}
+
+/// 活动 TCP 连接
+struct ConnectionsCollector {
+    system: Arc<SystemCollector>,
+}
+
+impl ConnectionsCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system }
+    }
+}
+
+#[async_trait]
+impl Collector for ConnectionsCollector {
+    type Output = ConnectionsInfo;
+
+    fn name(&self) -> &'static str {
+        "connections"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_connections
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> ConnectionsInfo {
+        self.system.collect_connection_info().await
+    }
+
+    fn apply(connections: ConnectionsInfo, data: &mut RealtimeData) {
+        data.connections = connections;
+    }
+}
+
+/// 按 CPU 使用率排序的进程列表
+struct ProcessesCollector {
+    system: Arc<SystemCollector>,
+    sys: System,
+}
+
+impl ProcessesCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system, sys: System::new_all() }
+    }
+}
+
+#[async_trait]
+impl Collector for ProcessesCollector {
+    type Output = Vec<ProcessInfo>;
+
+    fn name(&self) -> &'static str {
+        "processes"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_processes
+    }
+
+    async fn collect(&mut self, latest: &SharedSnapshot) -> Vec<ProcessInfo> {
+        // 占用 GPU 的进程不论 CPU 占用都要保留，推送前才能把显存占用关联上
+        let gpu_pids: HashSet<u32> = latest.read().await.gpu.iter()
+            .flatten()
+            .flat_map(|gpu| gpu.processes.iter().map(|p| p.pid))
+            .collect();
+        self.sys.refresh_memory();
+        self.sys.refresh_processes();
+        top_processes(collect_process_info(&self.sys), self.system.config().max_processes, &gpu_pids)
+    }
+
+    fn apply(processes: Vec<ProcessInfo>, data: &mut RealtimeData) {
+        data.processes = processes;
+    }
+}
+
+/// Docker 容器状态和资源占用
+struct DockerCollector {
+    system: Arc<SystemCollector>,
+    rates: RateTracker,
+}
+
+impl DockerCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system, rates: RateTracker::new() }
+    }
+}
+
+#[async_trait]
+impl Collector for DockerCollector {
+    type Output = Vec<DockerContainer>;
+
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_docker
+    }
+
+    // docker stats --no-stream 本身需要一两秒，默认不低于 5 秒
+    fn default_interval(&self, config: &GlobalConfig) -> Duration {
+        Duration::from_secs(config.collectors.collect_interval_secs.max(5))
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Vec<DockerContainer> {
+        let containers = self.system.collect_docker_containers(&mut self.rates).await;
+        self.rates.prune(RATE_MAX_AGE, std::time::Instant::now());
+        containers
+    }
+
+    fn apply(docker_containers: Vec<DockerContainer>, data: &mut RealtimeData) {
+        data.docker_containers = docker_containers;
+    }
+}
+
+/// hwmon 传感器，以及由此得到的 CPU 温度和 RAPL 功耗
+struct SensorsCollector {
+    system: Arc<SystemCollector>,
+    rates: RateTracker,
+}
+
+impl SensorsCollector {
+    fn new(system: Arc<SystemCollector>) -> Self {
+        Self { system, rates: RateTracker::new() }
+    }
+}
+
+#[async_trait]
+impl Collector for SensorsCollector {
+    type Output = (Vec<SensorChip>, Vec<TemperatureSensor>, Option<f32>, Option<f32>);
+
+    fn name(&self) -> &'static str {
+        "sensors"
+    }
+
+    fn enabled_by_default(&self, config: &GlobalConfig) -> bool {
+        config.collectors.enable_sensors
+    }
+
+    async fn collect(&mut self, _latest: &SharedSnapshot) -> Self::Output {
+        let sensors = read_hwmon_chips(Path::new("/sys"));
+        let (temperatures, temperature, power) = self.system.collect_cpu_sensors_native(&sensors, &mut self.rates).await;
+        (sensors, temperatures, temperature, power)
+    }
+
+    fn apply((sensors, temperatures, temperature, power): Self::Output, data: &mut RealtimeData) {
+        data.sensors = sensors;
+        data.cpu.temperatures = temperatures;
+        data.cpu.temperature_celsius = temperature;
+        data.cpu.power_watts = power;
+    }
+}
+
 pub fn get_static_info() -> SystemStaticInfo {
     let sys = System::new_all();
     
@@ -293,7 +1256,7 @@ pub fn get_static_info() -> SystemStaticInfo {
 
 // 辅助函数
 
-fn collect_memory_info(sys: &System) -> MemoryInfo {
+fn collect_memory_info(sys: &System, rates: &mut RateTracker) -> MemoryInfo {
     let total_memory = sys.total_memory();
     let used_memory = sys.used_memory();
     let free_memory = sys.free_memory();
@@ -302,6 +1265,23 @@ fn collect_memory_info(sys: &System) -> MemoryInfo {
     let swap_used = sys.used_swap();
     let swap_free = sys.free_swap();
     
+    let meminfo = std::fs::read_to_string("/proc/meminfo")
+        .map(|content| parse_meminfo(&content))
+        .unwrap_or_default();
+    let field = |key: &str| meminfo.get(key).copied().unwrap_or(0);
+    
+    // 换页速率和 OOM 次数
+    let vmstat = std::fs::read_to_string("/proc/vmstat")
+        .map(|content| parse_vmstat(&content))
+        .unwrap_or_default();
+    let now = std::time::Instant::now();
+    let mut vm_rate = |key: &str| {
+        let value = *vmstat.get(key)?;
+        rates.rate_ulong(&format!("vmstat:{}", key), value, now)
+    };
+    let swap_in_pages_per_sec = vm_rate("pswpin");
+    let swap_out_pages_per_sec = vm_rate("pswpout");
+    
     MemoryInfo {
         total_kb: total_memory / 1024,
         used_kb: used_memory / 1024,
@@ -315,10 +1295,28 @@ fn collect_memory_info(sys: &System) -> MemoryInfo {
         } else {
             0.0
         },
+        available_kb: meminfo.get("MemAvailable").copied().unwrap_or(sys.available_memory() / 1024),
+        buffers_kb: field("Buffers"),
+        cached_kb: field("Cached"),
+        shared_kb: field("Shmem"),
+        slab_kb: field("Slab"),
+        slab_reclaimable_kb: field("SReclaimable"),
+        dirty_kb: field("Dirty"),
+        writeback_kb: field("Writeback"),
+        hugepages_total: field("HugePages_Total"),
+        hugepages_free: field("HugePages_Free"),
+        hugepage_size_kb: field("Hugepagesize"),
+        zfs_arc: std::fs::read_to_string("/proc/spl/kstat/zfs/arcstats")
+            .ok()
+            .and_then(|content| parse_arcstats(&content)),
+        swap_in_pages_per_sec,
+        swap_out_pages_per_sec,
+        oom_kills: vmstat.get("oom_kill").copied(),
     }
 }
 
-fn collect_disk_info(disks: &Disks) -> Vec<DiskInfo> {
+/// 已挂载文件系统的容量，目录大小分析也据此确定可扫描的挂载点
+pub fn collect_disk_info(disks: &Disks) -> Vec<DiskInfo> {
     disks.iter()
         .filter_map(|disk| {
             let total_space = disk.total_space();
@@ -329,8 +1327,12 @@ fn collect_disk_info(disks: &Disks) -> Vec<DiskInfo> {
             let available_space = disk.available_space();
             let used_space = total_space.saturating_sub(available_space);
             
+            let name = disk.name().to_string_lossy().to_string();
+            
             Some(DiskInfo {
-                name: disk.name().to_string_lossy().to_string(),
+                device: resolve_block_device(&name),
+                smart_health: None,
+                name,
                 mount_point: disk.mount_point().to_string_lossy().to_string(),
                 file_system: disk.file_system().to_string_lossy().to_string(),
                 total_gb: total_space as f64 / 1024.0 / 1024.0 / 1024.0,
@@ -342,49 +1344,82 @@ fn collect_disk_info(disks: &Disks) -> Vec<DiskInfo> {
         .collect()
 }
 
-fn collect_network_info(
-    networks: &Networks,
-    last_rx_bytes: u64,
-    last_tx_bytes: u64,
-    first_run: bool,
-) -> (NetworkInfo, u64, u64) {
-    let mut total_rx_bytes = 0u64;
-    let mut total_tx_bytes = 0u64;
-    
-    let interfaces: Vec<NetworkInterface> = networks
-        .iter()
-        .map(|(name, data)| {
-            total_rx_bytes += data.total_received();
-            total_tx_bytes += data.total_transmitted();
-            
-            NetworkInterface {
-                name: name.clone(),
-                ipv4: vec![],
-                ipv6: vec![],
+async fn collect_disk_io(disk_list: &[DiskInfo], rates: &mut RateTracker) -> Vec<DiskIoInfo> {
+    let content = match tokio::fs::read_to_string("/proc/diskstats").await {
+        Ok(content) => content,
+        Err(e) => {
+            debug!("Failed to read /proc/diskstats: {}", e);
+            return Vec::new();
+        }
+    };
+    let now = std::time::Instant::now();
+
+    parse_diskstats(&content)
+        .into_iter()
+        .filter_map(|sample| {
+            let mount_points: Vec<String> = disk_list.iter()
+                .filter(|d| d.device.as_deref() == Some(sample.name.as_str()))
+                .map(|d| d.mount_point.clone())
+                .collect();
+            if mount_points.is_empty() && is_pseudo_block_device(&sample.name) {
+                return None;
             }
+
+            let mut rate = |counter: &str, value: u64| {
+                rates.rate_ulong(&format!("disk:{}:{}", sample.name, counter), value, now).unwrap_or(0.0)
+            };
+
+            let read_iops = rate("reads", sample.reads_completed);
+            let write_iops = rate("writes", sample.writes_completed);
+            let read_ms_per_sec = rate("ms_reading", sample.ms_reading);
+            let write_ms_per_sec = rate("ms_writing", sample.ms_writing);
+            // await = 每个请求的平均耗时，同一时间窗口内的速率之比等于增量之比
+            let await_of = |ms: f64, ios: f64| if ios > 0.0 { ms / ios } else { 0.0 };
+
+            Some(DiskIoInfo {
+                read_bytes_per_sec: rate("sectors_read", sample.sectors_read) * DISKSTATS_SECTOR_SIZE as f64,
+                write_bytes_per_sec: rate("sectors_written", sample.sectors_written) * DISKSTATS_SECTOR_SIZE as f64,
+                avg_queue_depth: rate("weighted_ms", sample.weighted_ms_doing_io) / 1000.0,
+                utilization_percent: (rate("ms_doing_io", sample.ms_doing_io) / 10.0).min(100.0) as f32,
+                read_await_ms: await_of(read_ms_per_sec, read_iops),
+                write_await_ms: await_of(write_ms_per_sec, write_iops),
+                await_ms: await_of(read_ms_per_sec + write_ms_per_sec, read_iops + write_iops),
+                read_iops,
+                write_iops,
+                ios_in_progress: sample.ios_in_progress,
+                mount_points,
+                device: sample.name,
+            })
         })
-        .collect();
-    
-    let (rx_speed_kbps, tx_speed_kbps) = if first_run {
-        (0.0, 0.0)
-    } else {
-        let rx_diff = total_rx_bytes.saturating_sub(last_rx_bytes);
-        let tx_diff = total_tx_bytes.saturating_sub(last_tx_bytes);
-        (rx_diff as f64 / 1024.0, tx_diff as f64 / 1024.0)
-    };
-    
-    let network_info = NetworkInfo {
-        interfaces,
-        rx_speed_kbps,
-        tx_speed_kbps,
-        total_rx_gb: total_rx_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
-        total_tx_gb: total_tx_bytes as f64 / 1024.0 / 1024.0 / 1024.0,
-    };
-    
-    (network_info, total_rx_bytes, total_tx_bytes)
+        .collect()
+}
+
+fn snapshots_from_sysinfo(networks: &Networks) -> Vec<InterfaceSnapshot> {
+    networks
+        .iter()
+        .map(|(name, data)| InterfaceSnapshot {
+            name: name.clone(),
+            counters: InterfaceCounters {
+                rx_bytes: data.total_received(),
+                tx_bytes: data.total_transmitted(),
+                rx_packets: data.total_packets_received(),
+                tx_packets: data.total_packets_transmitted(),
+                rx_errors: data.total_errors_on_received(),
+                tx_errors: data.total_errors_on_transmitted(),
+                rx_dropped: 0,
+                tx_dropped: 0,
+            },
+            mtu: None,
+            mac_address: Some(data.mac_address().to_string()),
+            operstate: "unknown".to_string(),
+            is_up: true,
+            speed_mbps: None,
+            is_virtual: is_virtual_interface(name, Path::new("")),
+        })
+        .collect()
 }
 
-fn collect_process_info(sys: &System, max_processes: usize) -> Vec<ProcessInfo> {
+fn collect_process_info(sys: &System) -> Vec<ProcessInfo> {
     let mut processes: Vec<ProcessInfo> = Vec::new();
     let total_memory = sys.total_memory();
     
@@ -414,16 +1449,82 @@ fn collect_process_info(sys: &System, max_processes: usize) -> Vec<ProcessInfo>
             user,
             command: process.cmd().join(" "),
             start_time: Some(process.start_time() as i64),
+            gpu_memory_mb: None,
+            gpu_ids: Vec::new(),
         });
     }
     
-    // 按 CPU 使用率排序，取前 N 个
+    processes
+}
+
+/// 按 CPU 使用率排序取前 N 个，`keep` 中的进程（GPU 进程）排在之后也保留
+fn top_processes(mut processes: Vec<ProcessInfo>, max_processes: usize, keep: &HashSet<u32>) -> Vec<ProcessInfo> {
     processes.sort_by(|a, b| b.cpu_percent.partial_cmp(&a.cpu_percent).unwrap());
-    processes.truncate(max_processes);
-    
+    let mut rank = 0;
+    processes.retain(|process| {
+        rank += 1;
+        rank <= max_processes || keep.contains(&process.pid)
+    });
     processes
 }
 
+/// 推送前关联不同采集器的结果：SMART 健康状态挂到挂载点，GPU 进程关联到进程列表和容器
+fn link_snapshot(data: &mut RealtimeData) {
+    // 把健康状态挂到所在物理盘的各个挂载点上
+    for disk in &mut data.disks {
+        if let Some(device) = &disk.device {
+            let parent = parent_block_device(device);
+            disk.smart_health = data.smart.iter()
+                .find(|s| smart_device_matches(&s.device, &parent))
+                .map(|s| s.health_status.clone());
+        }
+    }
+
+    if let Some(gpus) = data.gpu.as_mut() {
+        link_gpu_processes(gpus, &mut data.processes, &mut data.docker_containers);
+    }
+}
+
+fn link_gpu_processes(gpus: &mut [GpuInfo], processes: &mut [ProcessInfo], containers: &mut [DockerContainer]) {
+    for gpu in gpus.iter_mut() {
+        for gpu_process in gpu.processes.iter_mut() {
+            link_gpu_process(&gpu.id, gpu_process, processes, containers);
+        }
+    }
+}
+
+/// 按 GPU id 记录进程使用的显卡（不同厂商的 index 可能相同），并累计进程和容器的显存占用
+fn link_gpu_process(gpu_id: &str, gpu_process: &mut GpuProcess, processes: &mut [ProcessInfo], containers: &mut [DockerContainer]) {
+    let used_memory_mb = gpu_process.used_memory_mb;
+
+    if let Some(process) = processes.iter_mut().find(|p| p.pid == gpu_process.pid) {
+        if !process.gpu_ids.iter().any(|id| id == gpu_id) {
+            process.gpu_ids.push(gpu_id.to_string());
+        }
+        if let Some(mb) = used_memory_mb {
+            process.gpu_memory_mb = Some(process.gpu_memory_mb.unwrap_or(0) + mb);
+        }
+    }
+
+    let Some(container_id) = std::fs::read_to_string(format!("/proc/{}/cgroup", gpu_process.pid))
+        .ok()
+        .and_then(|content| container_id_from_cgroup(&content))
+    else {
+        return;
+    };
+    // docker ps 输出的是 12 位短 ID
+    if let Some(container) = containers
+        .iter_mut()
+        .find(|c| !c.id.is_empty() && container_id.starts_with(&c.id))
+    {
+        gpu_process.container_name = Some(container.name.clone());
+        if let Some(mb) = used_memory_mb {
+            container.gpu_memory_mb = Some(container.gpu_memory_mb.unwrap_or(0) + mb);
+        }
+    }
+    gpu_process.container_id = Some(container_id);
+}
+
 // 解析函数
 
 fn parse_sensors_output(output: &str) -> (Option<f32>, Option<f32>) {
@@ -463,65 +1564,6 @@ fn parse_sensors_output(output: &str) -> (Option<f32>, Option<f32>) {
     (temperature, power)
 }
 
-fn parse_nvidia_smi_output(output: &str) -> Option<Vec<GpuInfo>> {
-    let mut gpus = Vec::new();
-    
-    for line in output.lines() {
-        let parts: Vec<&str> = line.split(", ").collect();
-        if parts.len() >= 7 {
-            // 使用 parse_with_default 进行安全解析
-            let index = parse_with_default(parts.get(1).unwrap_or(&"0"), 0u32);
-            let memory_total = parse_with_default(parts.get(2).unwrap_or(&"0"), 0u32);
-            let memory_used = parse_with_default(parts.get(3).unwrap_or(&"0"), 0u32);
-            let memory_free = parse_with_default(parts.get(4).unwrap_or(&"0"), 0u32);
-            let utilization = parse_with_default(parts.get(5).unwrap_or(&"0"), 0u8);
-            let temperature = parse_with_default(parts.get(6).unwrap_or(&"0"), 0u8);
-            
-            // 解析可选参数
-            let power_draw = parts.get(7)
-                .filter(|s| **s != "[N/A]")
-                .and_then(|s| s.parse::<f32>().ok());
-            
-            let power_limit = parts.get(8)
-                .filter(|s| **s != "[N/A]")
-                .and_then(|s| s.parse::<f32>().ok());
-            
-            let fan_speed = parts.get(9)
-                .filter(|s| **s != "[N/A]")
-                .and_then(|s| s.parse::<u8>().ok());
-            
-            let graphics_clock = parts.get(10)
-                .filter(|s| **s != "[N/A]")
-                .and_then(|s| s.parse::<u32>().ok());
-            
-            let memory_clock = parts.get(11)
-                .filter(|s| **s != "[N/A]")
-                .and_then(|s| s.parse::<u32>().ok());
-            
-            gpus.push(GpuInfo {
-                name: parts.get(0).unwrap_or(&"Unknown").to_string(),
-                index,
-                memory_total_mb: memory_total,
-                memory_used_mb: memory_used,
-                memory_free_mb: memory_free,
-                utilization_percent: utilization,
-                temperature_celsius: temperature,
-                power_draw_watts: power_draw,
-                power_limit_watts: power_limit,
-                fan_speed_percent: fan_speed,
-                graphics_clock_mhz: graphics_clock,
-                memory_clock_mhz: memory_clock,
-            });
-        }
-    }
-    
-    if !gpus.is_empty() {
-        Some(gpus)
-    } else {
-        None
-    }
-}
-
 fn parse_ss_output(output: &str) -> Vec<PortInfo> {
     let mut ports = Vec::new();
     
@@ -619,4 +1661,65 @@ fn parse_netstat_output(output: &str) -> Vec<PortInfo> {
     
     ports.sort_by_key(|p| p.port);
     ports
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, cpu_percent: f32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: format!("p{}", pid),
+            cpu_percent,
+            memory_percent: 0.0,
+            memory_mb: 0.0,
+            status: "Running".to_string(),
+            user: None,
+            command: String::new(),
+            start_time: None,
+            gpu_memory_mb: None,
+            gpu_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_gpu_processes_beyond_the_limit() {
+        let processes = vec![process(1, 5.0), process(2, 50.0), process(3, 0.0), process(4, 20.0)];
+        let keep = HashSet::from([3]);
+        let pids: Vec<u32> = top_processes(processes, 2, &keep).iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [2, 4, 3]);
+    }
+
+    fn gpu(vendor: &str, pci_bus_id: &str, pids: &[(u32, u32)]) -> GpuInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": crate::gpu::gpu_id(vendor, Some(pci_bus_id), 0),
+            "vendor": vendor,
+            "name": vendor,
+            "index": 0,
+            "throttle_reasons": [],
+            "processes": pids.iter().map(|(pid, mb)| serde_json::json!({
+                "pid": pid,
+                "name": "p",
+                "used_memory_mb": mb,
+                "process_type": "compute",
+            })).collect::<Vec<_>>(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn links_processes_by_gpu_id() {
+        // 不同厂商的 GPU index 都是 0
+        let mut gpus = vec![
+            gpu("nvidia", "00000000:01:00.0", &[(3, 100), (u32::MAX, 1)]),
+            gpu("amd", "0000:03:00.0", &[(3, 20)]),
+        ];
+        let mut processes = vec![process(3, 0.0), process(4, 0.0)];
+        link_gpu_processes(&mut gpus, &mut processes, &mut []);
+
+        assert_eq!(processes[0].gpu_ids, ["nvidia:00000000:01:00.0", "amd:0000:03:00.0"]);
+        assert_eq!(processes[0].gpu_memory_mb, Some(120));
+        assert!(processes[1].gpu_ids.is_empty());
+        assert_eq!(processes[1].gpu_memory_mb, None);
+    }
+}
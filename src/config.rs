@@ -1,39 +1,344 @@
+use crate::config_edit;
+use crate::config_env::{self, EnvOverride};
 use crate::handlers::check_service_health;
 use crate::models::{Config, ServiceCard, ServiceCardConfig, ServicesConfig};
+use std::collections::HashSet;
+use std::ops::Range;
 use std::path::Path;
 use tokio::fs;
-use tracing::{error, info};
-
-pub async fn load_config() -> Config {
-    // 尝试读取 config.toml
-    let toml_path = Path::new("config.toml");
-    if toml_path.exists() {
-        match fs::read_to_string(toml_path).await {
-            Ok(content) => match toml::from_str::<Config>(&content) {
-                Ok(config) => {
-                    info!("Loaded configuration from config.toml");
-                    return config;
-                }
-                Err(e) => {
-                    error!("Failed to parse config.toml: {}", e);
+use tracing::{error, info, warn};
+
+/// 配置文件路径
+pub const CONFIG_PATH: &str = "config.toml";
+
+/// 配置文件中的一个问题
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    /// 出错的键路径，如 `services.items[1].port`；语法错误时为 None
+    pub key: Option<String>,
+    pub message: String,
+    /// 行号和列号（从 1 开始）
+    pub location: Option<(usize, usize)>,
+}
+
+impl ConfigError {
+    fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { key: Some(key.into()), message: message.into(), location: None }
+    }
+
+    /// 形如 `config.toml:12:8: services.items[1].name: duplicate service name "Gitea"`
+    pub fn describe(&self, file: &Path) -> String {
+        let location = self
+            .location
+            .map(|(line, column)| format!(":{}:{}", line, column))
+            .unwrap_or_default();
+        let key = self.key.as_ref().map(|key| format!("{}: ", key)).unwrap_or_default();
+        format!("{}{}: {}{}", file.display(), location, key, self.message)
+    }
+}
+
+/// 命令行对配置文件的覆盖，热加载时同样生效
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub static_dir: Option<String>,
+}
+
+impl ConfigOverrides {
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(host) = &self.host {
+            config.server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(static_dir) = &self.static_dir {
+            config.server.static_dir = Some(static_dir.clone());
+        }
+    }
+
+    /// 被覆盖的键及对应的命令行参数
+    pub fn sources(&self) -> Vec<(&'static str, &'static str)> {
+        let mut sources = Vec::new();
+        if self.host.is_some() {
+            sources.push(("server.host", "--host"));
+        }
+        if self.port.is_some() {
+            sources.push(("server.port", "--port"));
+        }
+        if self.static_dir.is_some() {
+            sources.push(("server.static_dir", "--static-dir"));
+        }
+        sources
+    }
+}
+
+/// 读取配置文件并叠加 `INDEX_RS_` 环境变量和命令行覆盖
+///
+/// 文件缺失或无效时记录所有问题；只有 `allow_defaults` 为 true 时才回退到内置的默认配置
+/// （覆盖仍然生效），否则返回 None，由调用方决定退出。
+pub async fn load_config(path: &Path, allow_defaults: bool, overrides: &ConfigOverrides) -> Option<Config> {
+    let env = config_env::from_env();
+    let config = match read_config(path, &env, overrides).await {
+        Ok(config) => {
+            info!("Loaded configuration from {}", path.display());
+            config
+        }
+        Err(errors) => {
+            for e in &errors {
+                error!("{}", e);
+            }
+            if !allow_defaults {
+                error!("Fix {} or start with --allow-default-config to use the defaults", path.display());
+                return None;
+            }
+            warn!("Using default configuration");
+            let defaults = toml::to_string(&default_config()).unwrap_or_default();
+            match parse_config(&defaults, &env, overrides) {
+                Ok(config) => config,
+                Err(errors) => {
+                    for e in &errors {
+                        error!("{}", e.describe(Path::new("defaults")));
+                    }
+                    return None;
                 }
-            },
-            Err(e) => {
-                error!("Failed to read config.toml: {}", e);
             }
         }
+    };
+
+    for item in &env {
+        info!("{} overrides {}", item.var, item.key);
     }
+    Some(config)
+}
+
+/// 按 serve 的方式（含环境变量和命令行覆盖）校验配置文件并把问题输出到 stderr，返回是否有效（check-config）
+pub async fn check_config(path: &Path, overrides: &ConfigOverrides) -> bool {
+    let env = config_env::from_env();
+    match read_config(path, &env, overrides).await {
+        Ok(config) => {
+            println!(
+                "{}: OK ({} services, {} environment overrides)",
+                path.display(),
+                config.services.items.len(),
+                env.len()
+            );
+            true
+        }
+        Err(errors) => {
+            for e in &errors {
+                eprintln!("{}", e);
+            }
+            eprintln!("{}: {} problem(s) found", path.display(), errors.len());
+            false
+        }
+    }
+}
 
-    // 如果 config.toml 不存在或解析失败，返回默认配置
-    info!("Using default configuration");
+/// 读取并校验配置文件，错误信息带文件名和行列号
+pub async fn read_config(path: &Path, env: &[EnvOverride], overrides: &ConfigOverrides) -> Result<Config, Vec<String>> {
+    let content = fs::read_to_string(path)
+        .await
+        .map_err(|e| vec![format!("{}: {}", path.display(), e)])?;
+    parse_config(&content, env, overrides).map_err(|errors| errors.iter().map(|e| e.describe(path)).collect())
+}
+
+/// 内置的默认配置
+pub fn default_config() -> Config {
     Config {
         server: crate::models::ServerConfig {
             host: "0.0.0.0".to_string(),
             port: 9876,
+            static_dir: None,
+            admin_token: None,
+            realtime: crate::models::RealtimeConfig::default(),
         },
         services: get_default_services_config(),
         monitoring: crate::collector_config::GlobalConfig::default(),
+        files: crate::models::FilesConfig::default(),
+        disk_usage: crate::models::DiskUsageConfig::default(),
+    }
+}
+
+/// 解析配置文件内容，叠加环境变量和命令行覆盖后校验
+///
+/// 优先级：默认值 < 文件 < 环境变量 < 命令行。
+pub fn parse_config(content: &str, env: &[EnvOverride], overrides: &ConfigOverrides) -> Result<Config, Vec<ConfigError>> {
+    let file_error = |e: toml::de::Error| {
+        vec![ConfigError {
+            key: None,
+            message: e.message().to_string(),
+            location: e.span().map(|span| line_column(content, span.start)),
+        }]
+    };
+
+    let mut table = content.parse::<toml::Table>().map_err(file_error)?;
+    let mut config = if env.is_empty() {
+        toml::from_str::<Config>(content).map_err(file_error)?
+    } else {
+        let defaults = toml::Table::try_from(default_config()).unwrap_or_default();
+        config_env::apply(&mut table, env, &defaults).map_err(|errors| {
+            errors
+                .into_iter()
+                .map(|message| ConfigError { key: None, message, location: None })
+                .collect::<Vec<_>>()
+        })?;
+        match toml::Value::Table(table.clone()).try_into::<Config>() {
+            Ok(config) => config,
+            Err(e) => {
+                // 文件本身有误时给出行列号，否则是环境变量的值不对
+                toml::from_str::<Config>(content).map_err(file_error)?;
+                let vars: Vec<&str> = env.iter().map(|item| item.var.as_str()).collect();
+                return Err(vec![ConfigError {
+                    key: None,
+                    message: format!("{} (after applying {})", e.message(), vars.join(", ")),
+                    location: None,
+                }]);
+            }
+        }
+    };
+
+    overrides.apply(&mut config);
+
+    // 反序列化会忽略不认识的键，与解析结果对比找出拼错或不存在的键
+    let parsed = serde_json::to_value(&table).unwrap_or_default();
+    let known = serde_json::to_value(&config).unwrap_or_default();
+    let mut errors: Vec<ConfigError> = config_edit::unknown_keys(&parsed, &known)
+        .into_iter()
+        .map(|key| ConfigError::new(key, "unknown key"))
+        .collect();
+    errors.extend(validate_config(&config));
+    if errors.is_empty() {
+        return Ok(config);
     }
+    // 来自命令行或环境变量的值标明来源，其余按键路径找到文件中的行列
+    let document = toml_edit::ImDocument::parse(content).ok();
+    let flags = overrides.sources();
+    for error in &mut errors {
+        let Some(key) = error.key.as_deref() else { continue };
+        if let Some((_, flag)) = flags.iter().find(|(source, _)| *source == key) {
+            error.message = format!("{} (set by {})", error.message, flag);
+        } else if let Some(item) = env.iter().rev().find(|item| item.covers(key)) {
+            error.message = format!("{} (set by {})", error.message, item.var);
+        } else if let Some(document) = &document {
+            error.location = locate(document, key).map(|span| line_column(content, span.start));
+        }
+    }
+    Err(errors)
+}
+
+/// 检查无法由类型表达的约束，返回所有问题
+pub fn validate_config(config: &Config) -> Vec<ConfigError> {
+    let mut errors = Vec::new();
+
+    let server = &config.server;
+    if server.port == 0 {
+        errors.push(ConfigError::new("server.port", "must be between 1 and 65535"));
+    }
+    if let Some(dir) = &server.static_dir
+        && let Err(e) = std::fs::read_dir(dir)
+    {
+        errors.push(ConfigError::new("server.static_dir", format!("{} is not a readable directory: {}", dir, e)));
+    }
+    if let Some(dir) = &config.files.storage_path
+        && let Err(e) = std::fs::read_dir(dir)
+    {
+        errors.push(ConfigError::new("files.storage_path", format!("{} is not a readable directory: {}", dir, e)));
+    }
+
+    let disk_usage = &config.disk_usage;
+    for (index, path) in disk_usage.allowed_paths.iter().enumerate() {
+        if !path.starts_with('/') {
+            errors.push(ConfigError::new(format!("disk_usage.allowed_paths[{}]", index), "must be an absolute path"));
+        }
+    }
+    if disk_usage.max_concurrent_scans == 0 {
+        errors.push(ConfigError::new("disk_usage.max_concurrent_scans", "must be greater than 0"));
+    }
+    if server.admin_token.as_deref().is_some_and(|token| token.trim().is_empty()) {
+        errors.push(ConfigError::new("server.admin_token", "must not be empty"));
+    }
+    if server.realtime.max_connections == 0 {
+        errors.push(ConfigError::new("server.realtime.max_connections", "must be greater than 0"));
+    }
+    if server.realtime.ping_interval_secs == 0 {
+        errors.push(ConfigError::new("server.realtime.ping_interval_secs", "must be greater than 0"));
+    }
+    if server.realtime.pong_timeout_secs <= server.realtime.ping_interval_secs {
+        errors.push(ConfigError::new(
+            "server.realtime.pong_timeout_secs",
+            "must be greater than server.realtime.ping_interval_secs",
+        ));
+    }
+
+    let mut names = HashSet::new();
+    for (index, item) in config.services.items.iter().enumerate() {
+        let key = format!("services.items[{}]", index);
+        if item.name.trim().is_empty() {
+            errors.push(ConfigError::new(format!("{}.name", key), "must not be empty"));
+        } else if !names.insert(item.name.as_str()) {
+            errors.push(ConfigError::new(format!("{}.name", key), format!("duplicate service name \"{}\"", item.name)));
+        }
+        if item.port == 0 {
+            errors.push(ConfigError::new(format!("{}.port", key), "must be between 1 and 65535"));
+        }
+        if !matches!(item.protocol.as_str(), "http" | "https") {
+            errors.push(ConfigError::new(
+                format!("{}.protocol", key),
+                format!("unsupported protocol \"{}\", expected http or https", item.protocol),
+            ));
+        }
+        if let Some(path) = &item.health_check_path
+            && !path.starts_with('/')
+        {
+            errors.push(ConfigError::new(format!("{}.health_check_path", key), "must start with /"));
+        }
+    }
+
+    let collectors = &config.monitoring.collectors;
+    if collectors.collect_interval_secs == 0 {
+        errors.push(ConfigError::new("monitoring.collectors.collect_interval_secs", "must be greater than 0"));
+    }
+    for (name, schedule) in &collectors.schedules {
+        let key = format!("monitoring.collectors.schedules.{}", name);
+        if schedule.interval_secs == Some(0) {
+            errors.push(ConfigError::new(format!("{}.interval_secs", key), "must be greater than 0"));
+        }
+        if schedule.timeout_secs == Some(0) {
+            errors.push(ConfigError::new(format!("{}.timeout_secs", key), "must be greater than 0"));
+        }
+    }
+
+    errors
+}
+
+/// 按 `a.b[1].c` 形式的键路径查找位置；键不在文件中（使用默认值）时返回最近的上级
+fn locate(document: &toml_edit::ImDocument<&str>, key: &str) -> Option<Range<usize>> {
+    let mut item = document.as_item();
+    let mut span = None;
+    for segment in key.split(['.', '[', ']']).filter(|segment| !segment.is_empty()) {
+        // 键本身的位置优先，隐式表（如只写了 `[a.b]` 时的 a）没有自己的值位置
+        let (key_span, next) = match segment.parse::<usize>() {
+            Ok(index) => (None, item.get(index)),
+            Err(_) => match item.as_table_like().and_then(|table| table.get_key_value(segment)) {
+                Some((key, value)) => (key.span(), Some(value)),
+                None => (None, None),
+            },
+        };
+        let Some(next) = next else { break };
+        item = next;
+        span = key_span.or_else(|| item.span()).or(span);
+    }
+    span
+}
+
+/// 字节偏移转换为行号和列号（从 1 开始）
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+    (line, column)
 }
 
 pub async fn get_services(config: &Config) -> Vec<ServiceCard> {
@@ -105,4 +410,118 @@ fn get_default_services_config() -> ServicesConfig {
             },
         ],
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"[server]
+host = "0.0.0.0"
+port = 9876
+
+[services]
+ip = "localhost"
+
+[[services.items]]
+name = "Gitea"
+port = 3000
+icon = "fab fa-git-alt"
+description = "Git 仓库管理"
+
+[[services.items]]
+name = "Jellyfin"
+port = 8096
+icon = "fas fa-play-circle"
+description = "媒体服务器"
+"#;
+
+    fn errors(content: &str, overrides: &ConfigOverrides) -> Vec<String> {
+        match parse_config(content, &[], overrides) {
+            Ok(_) => Vec::new(),
+            Err(errors) => errors.iter().map(|e| e.describe(Path::new("config.toml"))).collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_config() {
+        let config = parse_config(CONFIG, &[], &ConfigOverrides::default()).unwrap();
+        assert_eq!(config.services.items.len(), 2);
+        assert_eq!(config.server.port, 9876);
+    }
+
+    #[test]
+    fn reports_unknown_key_location() {
+        let content = CONFIG.replace("port = 9876", "port = 9876\nadmin_tokn = \"x\"");
+        assert_eq!(errors(&content, &ConfigOverrides::default()), ["config.toml:4:1: server.admin_tokn: unknown key"]);
+    }
+
+    #[test]
+    fn reports_type_error_location() {
+        let content = CONFIG.replace("port = 3000", "port = \"3000\"");
+        let errors = errors(&content, &ConfigOverrides::default());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("config.toml:10:8: "), "{}", errors[0]);
+    }
+
+    #[test]
+    fn rejects_duplicate_service_names() {
+        let content = CONFIG.replace("\"Jellyfin\"", "\"Gitea\"");
+        assert_eq!(
+            errors(&content, &ConfigOverrides::default()),
+            ["config.toml:15:1: services.items[1].name: duplicate service name \"Gitea\""]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_ports() {
+        let content = CONFIG.replace("port = 9876", "port = 0").replace("port = 8096", "port = 0");
+        assert_eq!(
+            errors(&content, &ConfigOverrides::default()),
+            [
+                "config.toml:3:1: server.port: must be between 1 and 65535",
+                "config.toml:16:1: services.items[1].port: must be between 1 and 65535",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_missing_storage_path() {
+        let content = format!("{}\n[files]\nstorage_path = \"/nonexistent/index-rs\"\n", CONFIG);
+        let errors = errors(&content, &ConfigOverrides::default());
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].starts_with("config.toml:21:1: files.storage_path: /nonexistent/index-rs is not a readable directory"),
+            "{}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn validates_command_line_overrides() {
+        let overrides = ConfigOverrides {
+            port: Some(8080),
+            static_dir: Some("/nonexistent/static".to_string()),
+            ..Default::default()
+        };
+        let errors = errors(CONFIG, &overrides);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("config.toml: server.static_dir: /nonexistent/static"), "{}", errors[0]);
+        assert!(errors[0].ends_with("(set by --static-dir)"), "{}", errors[0]);
+
+        let overrides = ConfigOverrides { port: Some(8080), ..Default::default() };
+        let config = parse_config(CONFIG, &[], &overrides).unwrap();
+        assert_eq!(config.server.port, 8080);
+    }
+
+    #[tokio::test]
+    async fn missing_file_fails_without_defaults() {
+        let path = std::env::temp_dir().join(format!("index-rs-missing-{}.toml", std::process::id()));
+        let overrides = ConfigOverrides::default();
+        assert!(load_config(&path, false, &overrides).await.is_none());
+        assert!(!check_config(&path, &overrides).await);
+
+        let overrides = ConfigOverrides { port: Some(8080), ..Default::default() };
+        let config = load_config(&path, true, &overrides).await.unwrap();
+        assert_eq!(config.server.port, 8080);
+    }
+}
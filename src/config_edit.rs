@@ -0,0 +1,310 @@
+use serde_json::{Map, Value as Json};
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
+/// 按 JSON Merge Patch（RFC 7396）修改 TOML 文档
+///
+/// 对象逐键合并，null 删除键，其余值整体替换。未涉及的键、注释和格式保持不变，
+/// 被替换的值保留原来的行尾注释。对象数组（如 services.items）整体替换，
+/// 但按 name 找到的原有项保留其注释和格式。
+pub fn merge_patch(document: &mut DocumentMut, patch: &Json) -> Result<(), String> {
+    let Json::Object(fields) = patch else {
+        return Err("patch must be a JSON object".to_string());
+    };
+    merge_table(document.as_table_mut(), fields, "")
+}
+
+fn merge_table(table: &mut dyn TableLike, fields: &Map<String, Json>, prefix: &str) -> Result<(), String> {
+    for (key, value) in fields {
+        let path = join_key(prefix, key);
+        if value.is_null() {
+            table.remove(key);
+            continue;
+        }
+        match table.get_mut(key) {
+            Some(item) => merge_item(item, value, &path)?,
+            None => {
+                let mut item = Item::None;
+                merge_item(&mut item, value, &path)?;
+                table.insert(key, item);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn merge_item(item: &mut Item, patch: &Json, path: &str) -> Result<(), String> {
+    match patch {
+        Json::Object(fields) => {
+            if item.as_table_like().is_none() {
+                let mut table = Table::new();
+                // 只含子表的新表不单独输出表头
+                table.set_implicit(true);
+                *item = Item::Table(table);
+            }
+            if let Some(table) = item.as_table_like_mut() {
+                merge_table(table, fields, path)?;
+            }
+        }
+        Json::Array(elements)
+            if !elements.is_empty() && elements.iter().all(Json::is_object) && !item.is_value() =>
+        {
+            if !item.is_array_of_tables() {
+                *item = Item::ArrayOfTables(ArrayOfTables::new());
+            }
+            if let Item::ArrayOfTables(tables) = item {
+                merge_array_of_tables(tables, elements, path)?;
+            }
+        }
+        _ => {
+            let mut value = to_toml_value(patch, path)?;
+            if let Item::Value(old) = item {
+                *value.decor_mut() = old.decor().clone();
+            }
+            *item = Item::Value(value);
+        }
+    }
+    Ok(())
+}
+
+fn merge_array_of_tables(tables: &mut ArrayOfTables, elements: &[Json], path: &str) -> Result<(), String> {
+    let mut existing: Vec<Option<Table>> = std::mem::take(tables).into_iter().map(Some).collect();
+    // 按新顺序重新分配原有的位置，写出的文件与补丁中的顺序一致
+    let mut positions: Vec<usize> = existing.iter().flatten().filter_map(Table::position).collect();
+    positions.sort_unstable();
+    let mut positions = positions.into_iter();
+
+    for (index, element) in elements.iter().enumerate() {
+        let Json::Object(fields) = element else { continue };
+        let mut table = existing
+            .iter_mut()
+            .find(|table| table.as_ref().is_some_and(|table| same_entry(table, fields)))
+            .and_then(Option::take)
+            .unwrap_or_default();
+        if table.position().is_some()
+            && let Some(position) = positions.next()
+        {
+            table.set_position(position);
+        }
+
+        // 数组是整体替换，新值中没有的键要删除
+        let stale: Vec<String> = table
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .filter(|key| !fields.contains_key(key))
+            .collect();
+        for key in stale {
+            table.remove(&key);
+        }
+        merge_table(&mut table, fields, &format!("{}[{}]", path, index))?;
+        tables.push(table);
+    }
+    Ok(())
+}
+
+/// 按 name 字段对应新旧元素，使各项的注释和格式跟随该项，而不是跟随下标
+fn same_entry(table: &Table, fields: &Map<String, Json>) -> bool {
+    match (table.get("name").and_then(Item::as_str), fields.get("name").and_then(Json::as_str)) {
+        (Some(old), Some(new)) => old == new,
+        _ => false,
+    }
+}
+
+fn to_toml_value(value: &Json, path: &str) -> Result<Value, String> {
+    Ok(match value {
+        Json::Null => return Err(format!("{}: null can only be used to remove a key", path)),
+        Json::Bool(value) => Value::from(*value),
+        Json::Number(number) => match (number.as_i64(), number.as_f64()) {
+            (Some(value), _) => Value::from(value),
+            (None, Some(value)) => Value::from(value),
+            (None, None) => return Err(format!("{}: unsupported number {}", path, number)),
+        },
+        Json::String(value) => Value::from(value.as_str()),
+        Json::Array(elements) => {
+            let mut array = Array::new();
+            for (index, element) in elements.iter().enumerate() {
+                array.push(to_toml_value(element, &format!("{}[{}]", path, index))?);
+            }
+            Value::Array(array)
+        }
+        Json::Object(fields) => {
+            let mut table = InlineTable::new();
+            for (key, element) in fields {
+                if !element.is_null() {
+                    table.insert(key, to_toml_value(element, &join_key(path, key))?);
+                }
+            }
+            Value::InlineTable(table)
+        }
+    })
+}
+
+/// 补丁中在合并后的配置里找不到的键（拼写错误或不存在的字段）
+pub fn unknown_keys(patch: &Json, config: &Json) -> Vec<String> {
+    let mut unknown = Vec::new();
+    if let Json::Object(fields) = patch {
+        collect_unknown_keys(fields, config, "", &mut unknown);
+    }
+    unknown
+}
+
+fn collect_unknown_keys(fields: &Map<String, Json>, target: &Json, prefix: &str, unknown: &mut Vec<String>) {
+    for (key, value) in fields {
+        let path = join_key(prefix, key);
+        match (target.get(key), value) {
+            (None, value) if !value.is_null() => unknown.push(path),
+            (Some(target), Json::Object(fields)) => collect_unknown_keys(fields, target, &path, unknown),
+            (Some(Json::Array(targets)), Json::Array(elements)) => {
+                for (index, (target, element)) in targets.iter().zip(elements).enumerate() {
+                    if let Json::Object(fields) = element {
+                        collect_unknown_keys(fields, target, &format!("{}[{}]", path, index), unknown);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) }
+}
+
+/// 原子地写入配置文件，返回备份路径
+///
+/// 先写入同目录下的临时文件并同步到磁盘，再把旧文件复制为 `<name>.bak`，最后改名覆盖，
+/// 任何一步失败都不会留下写了一半的配置文件。
+pub async fn write_atomically(path: &Path, content: &str) -> io::Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "config path has no file name"))?
+        .to_string_lossy()
+        .into_owned();
+    let temp = path.with_file_name(format!(".{}.tmp", file_name));
+    let backup = path.with_file_name(format!("{}.bak", file_name));
+
+    let result = async {
+        let mut file = fs::File::create(&temp).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+
+        if let Ok(metadata) = fs::metadata(path).await {
+            fs::set_permissions(&temp, metadata.permissions()).await?;
+            fs::copy(path, &backup).await?;
+        }
+        fs::rename(&temp, path).await
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp).await;
+    }
+    result.map(|()| backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CONFIG: &str = r#"# 服务器设置
+[server]
+host = "0.0.0.0"
+port = 9876 # 监听端口
+
+[monitoring.collectors]
+enable_gpu = true
+exclude = ["lo"]
+
+# 服务列表
+[[services.items]]
+# 网站
+name = "web"
+port = 80
+
+[[services.items]]
+name = "db"
+port = 5432
+"#;
+
+    fn patched(patch: Json) -> String {
+        let mut document: DocumentMut = CONFIG.parse().unwrap();
+        merge_patch(&mut document, &patch).unwrap();
+        document.to_string()
+    }
+
+    #[test]
+    fn replaces_values_and_keeps_comments() {
+        let output = patched(json!({"server": {"port": 8080}, "monitoring": {"collectors": {"exclude": ["lo", "docker0"]}}}));
+
+        assert!(output.contains("port = 8080 # 监听端口"));
+        assert!(output.contains(r#"exclude = ["lo", "docker0"]"#));
+        assert!(output.starts_with("# 服务器设置\n[server]\nhost = \"0.0.0.0\"\n"));
+        assert!(output.contains("enable_gpu = true"));
+    }
+
+    #[test]
+    fn null_removes_key_and_new_tables_are_added() {
+        let output = patched(json!({"server": {"host": null}, "files": {"storage_path": "/srv/files"}}));
+
+        assert!(!output.contains("host ="));
+        assert!(output.contains("[files]\nstorage_path = \"/srv/files\""));
+    }
+
+    #[test]
+    fn array_of_tables_follows_entries_by_name() {
+        let output = patched(json!({"services": {"items": [
+            {"name": "db", "port": 6432},
+            {"name": "web", "port": 80},
+            {"name": "cache", "port": 6379},
+        ]}}));
+
+        let document: DocumentMut = output.parse().unwrap();
+        let items = document["services"]["items"].as_array_of_tables().unwrap();
+        let names: Vec<&str> = items.iter().filter_map(|item| item["name"].as_str()).collect();
+        assert_eq!(names, ["db", "web", "cache"]);
+        assert_eq!(items.get(0).and_then(|item| item["port"].as_integer()), Some(6432));
+        // web 的注释跟随该项
+        assert!(output.contains("# 网站\nname = \"web\""));
+    }
+
+    #[test]
+    fn rejects_non_object_patch() {
+        let mut document: DocumentMut = CONFIG.parse().unwrap();
+
+        assert!(merge_patch(&mut document, &json!([1, 2])).is_err());
+        let error = merge_patch(&mut document, &json!({"monitoring": {"collectors": {"exclude": [null]}}})).unwrap_err();
+        assert_eq!(error, "monitoring.collectors.exclude[0]: null can only be used to remove a key");
+    }
+
+    #[test]
+    fn finds_unknown_keys() {
+        let config = json!({"server": {"port": 1}, "services": {"items": [{"name": "web", "port": 80}]}});
+        let patch = json!({
+            "server": {"prot": 2, "port": 3, "removed": null},
+            "services": {"items": [{"name": "web", "health_path": "/"}]},
+            "extra": {},
+        });
+
+        assert_eq!(unknown_keys(&patch, &config), ["server.prot", "services.items[0].health_path", "extra"]);
+    }
+
+    #[tokio::test]
+    async fn writes_atomically_with_backup() {
+        let dir = std::env::temp_dir().join(format!("config-edit-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "old = true\n").unwrap();
+
+        let backup = write_atomically(&path, "new = true\n").await.unwrap();
+
+        assert_eq!(backup, dir.join("config.toml.bak"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new = true\n");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "old = true\n");
+        assert!(!dir.join(".config.toml.tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,289 @@
+use toml::{Table, Value};
+
+/// 环境变量前缀，键路径各段以 `__` 分隔，如 `INDEX_RS_MONITORING__COLLECTORS__ENABLE_GPU`
+pub const ENV_PREFIX: &str = "INDEX_RS_";
+
+/// 旧版环境变量与对应的键路径，优先级低于同一键的 `INDEX_RS_` 变量
+const LEGACY_VARS: &[(&str, &str)] = &[("FILE_STORAGE_PATH", "files.storage_path")];
+
+/// 默认配置中不出现的可选键，`*` 匹配任意一段（数组下标或表名）
+const OPTIONAL_KEYS: &[&str] = &[
+    "server.static_dir",
+    "server.admin_token",
+    "files.storage_path",
+    "services.items.*.health_check_path",
+    "monitoring.collectors.schedules.*",
+    "monitoring.collectors.schedules.*.enabled",
+    "monitoring.collectors.schedules.*.interval_secs",
+    "monitoring.collectors.schedules.*.timeout_secs",
+];
+
+/// 一个环境变量覆盖
+#[derive(Debug, Clone)]
+pub struct EnvOverride {
+    /// 环境变量名
+    pub var: String,
+    /// 键路径，形如 `services.items[0].port`，与校验错误中的写法一致
+    pub key: String,
+    path: Vec<String>,
+    value: String,
+}
+
+impl EnvOverride {
+    fn new(var: String, path: Vec<String>, value: String) -> Self {
+        let mut key = String::new();
+        for segment in &path {
+            if segment.parse::<usize>().is_ok() {
+                key.push_str(&format!("[{}]", segment));
+            } else {
+                if !key.is_empty() {
+                    key.push('.');
+                }
+                key.push_str(segment);
+            }
+        }
+        Self { var, key, path, value }
+    }
+
+    /// 该覆盖是否决定了 `key` 处的值（相同或是其上级）
+    pub fn covers(&self, key: &str) -> bool {
+        key.strip_prefix(self.key.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+    }
+}
+
+/// 读取当前进程的覆盖项，按应用顺序排列（旧版变量在前）
+pub fn from_env() -> Vec<EnvOverride> {
+    parse_vars(std::env::vars())
+}
+
+fn parse_vars(vars: impl IntoIterator<Item = (String, String)>) -> Vec<EnvOverride> {
+    let mut legacy = Vec::new();
+    let mut overrides = Vec::new();
+
+    for (var, value) in vars {
+        if let Some(rest) = var.strip_prefix(ENV_PREFIX) {
+            let path: Vec<String> = rest.split("__").map(|segment| segment.to_ascii_lowercase()).collect();
+            if path.iter().all(|segment| !segment.is_empty()) {
+                overrides.push(EnvOverride::new(var, path, value));
+            }
+        } else if let Some((_, key)) = LEGACY_VARS.iter().find(|(name, _)| *name == var) {
+            let path = key.split('.').map(str::to_string).collect();
+            legacy.push(EnvOverride::new(var, path, value));
+        }
+    }
+
+    overrides.sort_by(|a, b| a.var.cmp(&b.var));
+    legacy.extend(overrides);
+    legacy
+}
+
+/// 把覆盖项写入配置文件解析出的表
+///
+/// 目标位置已有字符串值（来自文件或 `defaults`）时原样作为字符串，
+/// 否则按 TOML 值解析（`false`、`30`、`["a"]`、`{ name = "x" }`），解析失败时也作为字符串。
+/// 数组元素用数字下标指定，只能修改已有的元素。文件和 `defaults` 中都没有、
+/// 也不是已知可选键的路径视为拼写错误，报告对应的变量名。
+pub fn apply(table: &mut Table, overrides: &[EnvOverride], defaults: &Table) -> Result<(), Vec<String>> {
+    let mut root = Value::Table(std::mem::take(table));
+    let defaults = Value::Table(defaults.clone());
+    let mut errors = Vec::new();
+
+    for item in overrides {
+        let existing = lookup(&root, &item.path).or_else(|| lookup(&defaults, &item.path));
+        if existing.is_none() && !is_optional_key(&item.path) {
+            errors.push(format!("{}: {} is not a known config key", item.var, item.key));
+            continue;
+        }
+        let value = match existing {
+            Some(Value::String(_)) => Value::String(item.value.clone()),
+            _ => parse_value(&item.value),
+        };
+        if let Err(e) = assign(&mut root, &item.path, value) {
+            errors.push(format!("{}: cannot set {}: {}", item.var, item.key, e));
+        }
+    }
+
+    if let Value::Table(merged) = root {
+        *table = merged;
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+fn is_optional_key(path: &[String]) -> bool {
+    OPTIONAL_KEYS.iter().any(|pattern| {
+        let pattern: Vec<&str> = pattern.split('.').collect();
+        pattern.len() == path.len()
+            && pattern.iter().zip(path).all(|(expected, segment)| *expected == "*" || expected == segment)
+    })
+}
+
+fn parse_value(raw: &str) -> Value {
+    format!("value = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match value {
+        Value::Table(table) => table.get(segment),
+        Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+fn assign(target: &mut Value, path: &[String], value: Value) -> Result<(), String> {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+    let next = match target {
+        Value::Table(table) => table.entry(segment.clone()).or_insert_with(|| Value::Table(Table::new())),
+        Value::Array(items) => {
+            let len = items.len();
+            let index = segment
+                .parse::<usize>()
+                .map_err(|_| format!("{} is not an array index", segment))?;
+            items
+                .get_mut(index)
+                .ok_or_else(|| format!("index {} is out of range ({} items)", index, len))?
+        }
+        other => return Err(format!("the parent of {} is a {}, not a table", segment, other.type_str())),
+    };
+    assign(next, rest, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<EnvOverride> {
+        parse_vars(pairs.iter().map(|(var, value)| (var.to_string(), value.to_string())))
+    }
+
+    fn table(toml: &str) -> Table {
+        toml.parse().unwrap()
+    }
+
+    #[test]
+    fn maps_variable_names_to_key_paths() {
+        let overrides = vars(&[
+            ("INDEX_RS_SERVICES__ITEMS__1__PORT", "8080"),
+            ("INDEX_RS_MONITORING__COLLECTORS__ENABLE_GPU", "false"),
+            ("PATH", "/usr/bin"),
+            ("INDEX_RS_SERVER____PORT", "1"),
+        ]);
+
+        let keys: Vec<&str> = overrides.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, ["monitoring.collectors.enable_gpu", "services.items[1].port"]);
+        assert_eq!(overrides[1].path, ["services", "items", "1", "port"]);
+    }
+
+    #[test]
+    fn legacy_variables_apply_first() {
+        let overrides = vars(&[
+            ("INDEX_RS_FILES__STORAGE_PATH", "/srv/new"),
+            ("FILE_STORAGE_PATH", "/srv/old"),
+        ]);
+
+        let vars: Vec<&str> = overrides.iter().map(|o| o.var.as_str()).collect();
+        assert_eq!(vars, ["FILE_STORAGE_PATH", "INDEX_RS_FILES__STORAGE_PATH"]);
+
+        let mut config = Table::new();
+        apply(&mut config, &overrides, &Table::new()).unwrap();
+        assert_eq!(config["files"]["storage_path"].as_str(), Some("/srv/new"));
+    }
+
+    #[test]
+    fn covers_key_and_its_children() {
+        let overrides = vars(&[("INDEX_RS_SERVICES__ITEMS__0", "{}"), ("INDEX_RS_SERVER__PORT", "1")]);
+        let (server, item) = (&overrides[0], &overrides[1]);
+
+        assert!(item.covers("services.items[0]"));
+        assert!(item.covers("services.items[0].port"));
+        assert!(!item.covers("services.items[0]x"));
+        assert!(!item.covers("services.items[1].port"));
+        assert!(server.covers("server.port"));
+        assert!(!server.covers("server.port_range"));
+    }
+
+    #[test]
+    fn sets_array_elements_by_index() {
+        let mut config = table(
+            r#"
+            [[services.items]]
+            name = "web"
+            port = 80
+
+            [[services.items]]
+            name = "db"
+            port = 5432
+            "#,
+        );
+        let overrides = vars(&[
+            ("INDEX_RS_SERVICES__ITEMS__1__PORT", "6432"),
+            ("INDEX_RS_SERVICES__ITEMS__0__NAME", "true"),
+        ]);
+
+        apply(&mut config, &overrides, &Table::new()).unwrap();
+        let items = config["services"]["items"].as_array().unwrap();
+        assert_eq!(items[1]["port"].as_integer(), Some(6432));
+        // 原值是字符串时不按 TOML 解析
+        assert_eq!(items[0]["name"].as_str(), Some("true"));
+    }
+
+    #[test]
+    fn rejects_out_of_range_index() {
+        let mut config = table("[[services.items]]\nname = \"web\"\nport = 80\n");
+        let overrides = vars(&[("INDEX_RS_SERVICES__ITEMS__3__PORT", "1")]);
+
+        let errors = apply(&mut config, &overrides, &Table::new()).unwrap_err();
+        assert_eq!(errors, ["INDEX_RS_SERVICES__ITEMS__3__PORT: services.items[3].port is not a known config key"]);
+    }
+
+    #[test]
+    fn parses_values_as_toml_unless_target_is_string() {
+        let mut config = table("[server]\nhost = \"0.0.0.0\"\n");
+        let defaults = table("[server]\nport = 9876\n[monitoring.collectors]\nexclude = []\n");
+        let overrides = vars(&[
+            ("INDEX_RS_SERVER__HOST", "127.0.0.1"),
+            ("INDEX_RS_SERVER__PORT", "8080"),
+            ("INDEX_RS_MONITORING__COLLECTORS__EXCLUDE", r#"["lo", "docker0"]"#),
+            ("INDEX_RS_SERVER__STATIC_DIR", "not [valid toml"),
+        ]);
+
+        apply(&mut config, &overrides, &defaults).unwrap();
+        assert_eq!(config["server"]["host"].as_str(), Some("127.0.0.1"));
+        assert_eq!(config["server"]["port"].as_integer(), Some(8080));
+        assert_eq!(config["monitoring"]["collectors"]["exclude"].as_array().map(Vec::len), Some(2));
+        assert_eq!(config["server"]["static_dir"].as_str(), Some("not [valid toml"));
+    }
+
+    #[test]
+    fn reports_unknown_keys_but_allows_optional_ones() {
+        let mut config = Table::new();
+        let defaults = table("[server]\nport = 9876\n");
+        let overrides = vars(&[
+            ("INDEX_RS_SERVER__PROT", "1"),
+            ("INDEX_RS_SERVER__ADMIN_TOKEN", "secret"),
+            ("INDEX_RS_MONITORING__COLLECTORS__SCHEDULES__SMART__INTERVAL_SECS", "600"),
+        ]);
+
+        let errors = apply(&mut config, &overrides, &defaults).unwrap_err();
+        assert_eq!(errors, ["INDEX_RS_SERVER__PROT: server.prot is not a known config key"]);
+        assert_eq!(config["server"]["admin_token"].as_str(), Some("secret"));
+        assert_eq!(config["monitoring"]["collectors"]["schedules"]["smart"]["interval_secs"].as_integer(), Some(600));
+    }
+
+    #[test]
+    fn rejects_index_into_table() {
+        let mut config = table("[server]\nport = 1\n");
+        let overrides = vars(&[("INDEX_RS_SERVER__PORT__0", "1")]);
+
+        let errors = apply(&mut config, &overrides, &Table::new()).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("INDEX_RS_SERVER__PORT__0: "));
+    }
+}
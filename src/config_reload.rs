@@ -0,0 +1,458 @@
+use crate::collector_config::GlobalConfig;
+use crate::config::{default_config, parse_config, ConfigOverrides};
+use crate::config_edit;
+use crate::config_env;
+use crate::models::{Config, ConfigOverrideInfo, ConfigView};
+use notify::{RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{mpsc, watch, Mutex, RwLock};
+use tracing::{debug, error, info, warn};
+
+/// 文件变化后等待编辑器写完再读取
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 管理接口不能修改的段：监听地址、管理令牌以及可访问的目录只能在配置文件中修改
+const PROTECTED_SECTIONS: &[&str] = &["server", "files", "disk_usage"];
+
+/// 配置文件的热加载和写回
+///
+/// 服务列表和监控配置立即生效；server 段（监听地址、连接上限）以及
+/// history_size、disk_usage_cache_ttl_secs 需要重启。
+/// 校验失败的文件不会被应用，日志中列出它相对当前配置的改动。
+/// 文件监视、SIGHUP 和管理接口的写入共用同一把锁，依次处理。
+pub struct ConfigReloader {
+    path: PathBuf,
+    /// 命令行覆盖，每次重新加载后重新应用
+    overrides: ConfigOverrides,
+    config: Arc<RwLock<Config>>,
+    monitoring_tx: watch::Sender<GlobalConfig>,
+    /// 最近一次应用的文件内容
+    applied_text: Mutex<String>,
+    /// 文件中需要重启才生效的项与运行中的不同
+    restart_required: AtomicBool,
+}
+
+impl ConfigReloader {
+    pub async fn new(
+        path: PathBuf,
+        overrides: ConfigOverrides,
+        config: Arc<RwLock<Config>>,
+        monitoring_tx: watch::Sender<GlobalConfig>,
+    ) -> Arc<Self> {
+        let applied_text = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+        Arc::new(Self {
+            path,
+            overrides,
+            config,
+            monitoring_tx,
+            applied_text: Mutex::new(applied_text),
+            restart_required: AtomicBool::new(false),
+        })
+    }
+
+    /// 监视配置文件（以及 SIGHUP），变化时重新加载
+    pub fn spawn_watcher(self: &Arc<Self>) {
+        let reloader = self.clone();
+        tokio::spawn(async move {
+            let path = &reloader.path;
+            let (event_tx, mut event_rx) = mpsc::channel::<()>(16);
+
+            // 编辑器常以“写临时文件再改名”的方式保存，因此监视所在目录
+            let file_name = path.file_name().map(|name| name.to_os_string());
+            let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                if let Ok(event) = result
+                    && !event.kind.is_access()
+                    && event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name)
+                {
+                    let _ = event_tx.try_send(());
+                }
+            });
+            let watch_dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            let _watcher = match watcher {
+                Ok(mut watcher) => match watcher.watch(&watch_dir, RecursiveMode::NonRecursive) {
+                    Ok(()) => Some(watcher),
+                    Err(e) => {
+                        warn!("Failed to watch {}: {}, reload with SIGHUP only", watch_dir.display(), e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    warn!("Failed to create config watcher: {}, reload with SIGHUP only", e);
+                    None
+                }
+            };
+
+            let mut hangup = match signal(SignalKind::hangup()) {
+                Ok(hangup) => Some(hangup),
+                Err(e) => {
+                    warn!("Failed to install SIGHUP handler: {}", e);
+                    None
+                }
+            };
+
+            info!("Watching {} for configuration changes", path.display());
+
+            loop {
+                tokio::select! {
+                    Some(()) = event_rx.recv() => {
+                        tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                        while event_rx.try_recv().is_ok() {}
+                    }
+                    Some(()) = async { hangup.as_mut()?.recv().await } => {
+                        info!("Received SIGHUP, reloading {}", path.display());
+                    }
+                    else => break,
+                }
+
+                reloader.reload().await;
+            }
+        });
+    }
+
+    /// 重新读取配置文件，内容有变化且校验通过时应用
+    pub async fn reload(&self) {
+        let path = &self.path;
+        let mut applied_text = self.applied_text.lock().await;
+        let text = match tokio::fs::read_to_string(path).await {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Failed to read {}: {}", path.display(), e);
+                return;
+            }
+        };
+        if text == *applied_text {
+            debug!("{} unchanged", path.display());
+            return;
+        }
+
+        let diff = diff_toml(&applied_text, &text);
+        let new_config = match parse_config(&text, &config_env::from_env(), &self.overrides) {
+            Ok(new_config) => new_config,
+            Err(errors) => {
+                error!("Rejected {}:", path.display());
+                for e in &errors {
+                    error!("  {}", e.describe(path));
+                }
+                for line in &diff {
+                    error!("  rejected change: {}", line);
+                }
+                return;
+            }
+        };
+
+        info!("Reloading {} ({} changes)", path.display(), diff.len());
+        for line in &diff {
+            info!("  {}", line);
+        }
+        self.apply(new_config).await;
+        *applied_text = text;
+    }
+
+    /// 按 JSON Merge Patch 修改配置文件，校验通过后原子写回并立即应用
+    ///
+    /// 修改基于磁盘上的当前内容，注释和格式保持不变，旧文件保存为 `.bak`。
+    /// 文件不存在（以默认配置运行）时以内置默认配置为基础。[server]、[files] 和 [disk_usage] 不能通过这里修改。
+    pub async fn update(&self, patch: &serde_json::Value) -> Result<(), Vec<String>> {
+        let path = &self.path;
+        if let serde_json::Value::Object(fields) = patch {
+            let protected: Vec<String> = fields
+                .keys()
+                .filter(|key| PROTECTED_SECTIONS.contains(&key.as_str()))
+                .map(|key| format!("{}: cannot be changed via the admin API, edit {} instead", key, path.display()))
+                .collect();
+            if !protected.is_empty() {
+                return Err(protected);
+            }
+        }
+        let mut applied_text = self.applied_text.lock().await;
+        let current = match tokio::fs::read_to_string(path).await {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                toml::to_string(&default_config()).unwrap_or_default()
+            }
+            Err(e) => return Err(vec![format!("{}: {}", path.display(), e)]),
+        };
+
+        let mut document = current
+            .parse::<toml_edit::DocumentMut>()
+            .map_err(|e| vec![format!("{}: {}", path.display(), e.message())])?;
+        config_edit::merge_patch(&mut document, patch).map_err(|e| vec![e])?;
+        let text = document.to_string();
+
+        let new_config = parse_config(&text, &config_env::from_env(), &self.overrides)
+            .map_err(|errors| errors.iter().map(|e| e.describe(path)).collect::<Vec<_>>())?;
+        let unknown = config_edit::unknown_keys(patch, &serde_json::to_value(&new_config).unwrap_or_default());
+        if !unknown.is_empty() {
+            return Err(unknown.iter().map(|key| format!("{}: unknown key", key)).collect());
+        }
+
+        let backup = config_edit::write_atomically(path, &text)
+            .await
+            .map_err(|e| vec![format!("Failed to write {}: {}", path.display(), e)])?;
+        let diff = diff_toml(&current, &text);
+        info!("Updated {} via admin API ({} changes, previous version in {})", path.display(), diff.len(), backup.display());
+        for line in &diff {
+            info!("  {}", line);
+        }
+
+        self.apply(new_config).await;
+        *applied_text = text;
+        Ok(())
+    }
+
+    /// 管理接口返回的当前配置
+    pub async fn view(&self) -> ConfigView {
+        let mut overrides: Vec<ConfigOverrideInfo> = config_env::from_env()
+            .into_iter()
+            .map(|item| ConfigOverrideInfo { key: item.key, source: item.var })
+            .collect();
+        overrides.extend(self.overrides.sources().into_iter().map(|(key, flag)| ConfigOverrideInfo {
+            key: key.to_string(),
+            source: flag.to_string(),
+        }));
+
+        let mut config = self.config.read().await.clone();
+        config.server.admin_token = None;
+        ConfigView {
+            config,
+            overrides,
+            restart_required: self.restart_required.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn apply(&self, mut new_config: Config) {
+        let mut current = self.config.write().await;
+        let mut restart_required = false;
+        if new_config.server != current.server {
+            warn!("Changes to [server] take effect after restart");
+            new_config.server = current.server.clone();
+            restart_required = true;
+        }
+        // 推送历史和目录分析缓存在启动时创建，保持运行中的值
+        let performance = &mut new_config.monitoring.performance;
+        let running = &current.monitoring.performance;
+        if performance.history_size != running.history_size
+            || performance.disk_usage_cache_ttl_secs != running.disk_usage_cache_ttl_secs
+        {
+            warn!("Changes to history_size and disk_usage_cache_ttl_secs take effect after restart");
+            performance.history_size = running.history_size;
+            performance.disk_usage_cache_ttl_secs = running.disk_usage_cache_ttl_secs;
+            restart_required = true;
+        }
+        self.restart_required.store(restart_required, Ordering::Relaxed);
+
+        self.monitoring_tx.send_if_modified(|monitoring| {
+            if *monitoring == new_config.monitoring {
+                return false;
+            }
+            *monitoring = new_config.monitoring.clone();
+            true
+        });
+        *current = new_config;
+    }
+}
+
+/// 按键路径比较两份 TOML，返回 "+ 新增"、"- 删除"、"~ 修改" 形式的行
+fn diff_toml(old: &str, new: &str) -> Vec<String> {
+    let flatten_text = |text: &str| {
+        let mut entries = BTreeMap::new();
+        if let Ok(value) = text.parse::<toml::Table>() {
+            flatten(String::new(), &toml::Value::Table(value), &mut entries);
+        }
+        entries
+    };
+    let (old, new) = (flatten_text(old), flatten_text(new));
+
+    let mut lines = Vec::new();
+    for (key, old_value) in &old {
+        match new.get(key) {
+            None => lines.push(format!("- {} = {}", key, old_value)),
+            Some(new_value) if new_value != old_value => {
+                lines.push(format!("~ {}: {} -> {}", key, old_value, new_value))
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new_value) in &new {
+        if !old.contains_key(key) {
+            lines.push(format!("+ {} = {}", key, new_value));
+        }
+    }
+    lines
+}
+
+// 表按 a.b 展开，表数组按 a[0].b 展开，其余值作为叶子
+fn flatten(prefix: String, value: &toml::Value, entries: &mut BTreeMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(path, value, entries);
+            }
+        }
+        toml::Value::Array(items) if items.iter().any(|item| item.is_table()) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten(format!("{}[{}]", prefix, index), item, entries);
+            }
+        }
+        _ => {
+            entries.insert(prefix, value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CONFIG: &str = r#"[server]
+host = "0.0.0.0"
+port = 9876
+admin_token = "s3cret"
+
+[services]
+ip = "localhost"
+
+[[services.items]]
+name = "Gitea"
+port = 3000
+icon = "fab fa-git-alt"
+description = "Git 仓库管理"
+
+[monitoring.collectors]
+collect_interval_secs = 2
+"#;
+
+    /// 每个测试独立的配置文件，测试结束时删除所在目录
+    struct TempConfig(PathBuf);
+
+    impl TempConfig {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("config-reload-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let path = dir.join("config.toml");
+            std::fs::write(&path, CONFIG).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, text: &str) {
+            std::fs::write(&self.0, text).unwrap();
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            if let Some(dir) = self.0.parent() {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    async fn reloader(file: &TempConfig) -> (Arc<ConfigReloader>, watch::Receiver<GlobalConfig>) {
+        let config = parse_config(CONFIG, &[], &ConfigOverrides::default()).unwrap();
+        let (monitoring_tx, monitoring_rx) = watch::channel(config.monitoring.clone());
+        let reloader = ConfigReloader::new(
+            file.0.clone(),
+            ConfigOverrides::default(),
+            Arc::new(RwLock::new(config)),
+            monitoring_tx,
+        )
+        .await;
+        (reloader, monitoring_rx)
+    }
+
+    #[tokio::test]
+    async fn applies_valid_changes() {
+        let file = TempConfig::new("valid");
+        let (reloader, mut monitoring_rx) = reloader(&file).await;
+        let text = CONFIG.replace("collect_interval_secs = 2", "collect_interval_secs = 5").replace("port = 3000", "port = 3001");
+        file.write(&text);
+        reloader.reload().await;
+
+        assert!(monitoring_rx.has_changed().unwrap());
+        assert_eq!(monitoring_rx.borrow_and_update().collectors.collect_interval_secs, 5);
+        let view = reloader.view().await;
+        assert_eq!(view.config.services.items[0].port, 3001);
+        assert!(!view.restart_required);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_file_and_keeps_running_config() {
+        let file = TempConfig::new("invalid");
+        let (reloader, monitoring_rx) = reloader(&file).await;
+        let text = CONFIG.replace("collect_interval_secs = 2", "collect_interval_secs = 0").replace("port = 3000", "port = 3001");
+        file.write(&text);
+        reloader.reload().await;
+
+        assert!(!monitoring_rx.has_changed().unwrap());
+        let config = reloader.config.read().await;
+        assert_eq!(config.monitoring.collectors.collect_interval_secs, 2);
+        assert_eq!(config.services.items[0].port, 3000);
+        assert_eq!(*reloader.applied_text.lock().await, CONFIG);
+    }
+
+    #[tokio::test]
+    async fn keeps_settings_that_need_a_restart() {
+        let file = TempConfig::new("restart");
+        let (reloader, _monitoring_rx) = reloader(&file).await;
+        let text = CONFIG.replace("port = 9876", "port = 9877").replace("port = 3000", "port = 3001")
+            + "\n[monitoring.performance]\nhistory_size = 10\ndisk_usage_cache_ttl_secs = 1\n";
+        file.write(&text);
+        reloader.reload().await;
+
+        let view = reloader.view().await;
+        assert!(view.restart_required);
+        assert_eq!(view.config.server.port, 9876);
+        assert_eq!(view.config.services.items[0].port, 3001);
+        let defaults = GlobalConfig::default().performance;
+        assert_eq!(view.config.monitoring.performance.history_size, defaults.history_size);
+        assert_eq!(view.config.monitoring.performance.disk_usage_cache_ttl_secs, defaults.disk_usage_cache_ttl_secs);
+
+        // 改回运行中的值后不再提示重启
+        file.write(&CONFIG.replace("port = 3000", "port = 3002"));
+        reloader.reload().await;
+        assert!(!reloader.view().await.restart_required);
+    }
+
+    #[tokio::test]
+    async fn update_refuses_protected_sections() {
+        let file = TempConfig::new("protected");
+        let (reloader, _monitoring_rx) = reloader(&file).await;
+        for section in PROTECTED_SECTIONS {
+            let errors = reloader.update(&json!({ *section: {}, "services": { "ip": "nas" } })).await.unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].starts_with(&format!("{}: cannot be changed", section)), "{}", errors[0]);
+        }
+        assert_eq!(std::fs::read_to_string(&file.0).unwrap(), CONFIG);
+        assert_eq!(reloader.config.read().await.services.ip, "localhost");
+    }
+
+    #[tokio::test]
+    async fn view_hides_admin_token() {
+        let file = TempConfig::new("view");
+        let (reloader, _monitoring_rx) = reloader(&file).await;
+        let view = reloader.view().await;
+        assert_eq!(view.config.server.admin_token, None);
+        assert_eq!(reloader.config.read().await.server.admin_token.as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn diffs_by_key_path() {
+        let old = "a = 1\nb = \"x\"\n[c]\nd = true\n[[e]]\nf = 1\n";
+        let new = "a = 2\n[c]\nd = true\ng = [1, 2]\n[[e]]\nf = 1\n[[e]]\nf = 2\n";
+        assert_eq!(
+            diff_toml(old, new),
+            ["~ a: 1 -> 2", "- b = \"x\"", "+ c.g = [1, 2]", "+ e[1].f = 2"]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use crate::models::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// 解析 `ss -tnp -H` 的输出（不含监听套接字）
+pub fn parse_ss_connections(output: &str) -> Vec<ConnectionInfo> {
+    let mut connections = Vec::new();
+
+    for line in output.lines() {
+        let mut parts: Vec<&str> = line.split_whitespace().collect();
+        // 同时请求多种套接字类型时 ss 会额外输出 Netid 列
+        if parts.first().is_some_and(|p| *p == "tcp" || *p == "udp") {
+            parts.remove(0);
+        }
+        if parts.len() < 5 {
+            continue;
+        }
+
+        let state = parts[0];
+        if state == "LISTEN" || state == "UNCONN" {
+            continue;
+        }
+
+        let (Some((local_address, local_port)), Some((remote_address, remote_port))) =
+            (split_host_port(parts[3]), split_host_port(parts[4]))
+        else {
+            continue;
+        };
+
+        let (program, pid) = if parts.len() > 5 {
+            parse_ss_process(&parts[5..].join(" "))
+        } else {
+            ("Unknown".to_string(), None)
+        };
+
+        connections.push(ConnectionInfo {
+            protocol: "TCP".to_string(),
+            state: state.to_string(),
+            local_address,
+            local_port,
+            remote_address,
+            remote_port,
+            remote_hostname: None,
+            program,
+            pid,
+        });
+    }
+
+    connections
+}
+
+/// 解析 `netstat -tnp` 的输出
+pub fn parse_netstat_connections(output: &str) -> Vec<ConnectionInfo> {
+    let mut connections = Vec::new();
+
+    for line in output.lines().skip(2) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 6 || !parts[0].starts_with("tcp") {
+            continue;
+        }
+
+        let state = parts[5];
+        if state == "LISTEN" {
+            continue;
+        }
+
+        let (Some((local_address, local_port)), Some((remote_address, remote_port))) =
+            (split_host_port(parts[3]), split_host_port(parts[4]))
+        else {
+            continue;
+        };
+
+        let mut program = "Unknown".to_string();
+        let mut pid = None;
+        if let Some((pid_str, name)) = parts.get(6).and_then(|p| p.split_once('/'))
+            && let Ok(pid_val) = pid_str.parse::<u32>()
+        {
+            pid = Some(pid_val);
+            program = name.to_string();
+        }
+
+        connections.push(ConnectionInfo {
+            protocol: "TCP".to_string(),
+            state: state.to_string(),
+            local_address,
+            local_port,
+            remote_address,
+            remote_port,
+            remote_hostname: None,
+            program,
+            pid,
+        });
+    }
+
+    connections
+}
+
+/// 按远程主机和本地端口聚合连接，并截断明细列表
+pub fn aggregate_connections(mut connections: Vec<ConnectionInfo>, max_connections: usize) -> ConnectionsInfo {
+    let mut remote_map: HashMap<String, (Option<String>, usize, BTreeSet<u16>)> = HashMap::new();
+    let mut local_map: HashMap<u16, (String, usize, BTreeSet<String>)> = HashMap::new();
+
+    for conn in &connections {
+        let remote = remote_map
+            .entry(conn.remote_address.clone())
+            .or_insert_with(|| (conn.remote_hostname.clone(), 0, BTreeSet::new()));
+        remote.1 += 1;
+        remote.2.insert(conn.local_port);
+
+        let local = local_map
+            .entry(conn.local_port)
+            .or_insert_with(|| (conn.program.clone(), 0, BTreeSet::new()));
+        local.1 += 1;
+        local.2.insert(conn.remote_address.clone());
+        if local.0 == "Unknown" && conn.program != "Unknown" {
+            local.0 = conn.program.clone();
+        }
+    }
+
+    let mut by_remote_host: Vec<RemoteHostSummary> = remote_map
+        .into_iter()
+        .map(|(remote_address, (remote_hostname, connection_count, ports))| RemoteHostSummary {
+            remote_address,
+            remote_hostname,
+            connection_count,
+            local_ports: ports.into_iter().collect(),
+        })
+        .collect();
+    by_remote_host.sort_by(|a, b| {
+        b.connection_count
+            .cmp(&a.connection_count)
+            .then_with(|| a.remote_address.cmp(&b.remote_address))
+    });
+
+    let mut by_local_port: Vec<LocalPortSummary> = local_map
+        .into_iter()
+        .map(|(local_port, (program, connection_count, remotes))| LocalPortSummary {
+            local_port,
+            program,
+            connection_count,
+            remote_addresses: remotes.into_iter().collect(),
+        })
+        .collect();
+    by_local_port.sort_by(|a, b| {
+        b.connection_count
+            .cmp(&a.connection_count)
+            .then_with(|| a.local_port.cmp(&b.local_port))
+    });
+
+    let total = connections.len();
+    connections.sort_by(|a, b| {
+        a.local_port
+            .cmp(&b.local_port)
+            .then_with(|| a.remote_address.cmp(&b.remote_address))
+            .then_with(|| a.remote_port.cmp(&b.remote_port))
+    });
+    connections.truncate(max_connections);
+
+    ConnectionsInfo {
+        total,
+        connections,
+        by_remote_host,
+        by_local_port,
+    }
+}
+
+/// 判断地址是否值得做反向 DNS 解析
+pub fn is_resolvable_address(address: &str) -> bool {
+    match address.parse::<std::net::IpAddr>() {
+        Ok(ip) => !ip.is_loopback() && !ip.is_unspecified(),
+        Err(_) => false,
+    }
+}
+
+/// 解析 `getent hosts <ip>` 的输出，返回第一个主机名
+pub fn parse_getent_hosts(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .map(|name| name.to_string())
+}
+
+// 将 "1.2.3.4:80"、"[::1]:80"、"[::ffff:1.2.3.4]:80" 拆分为地址和端口
+fn split_host_port(addr: &str) -> Option<(String, u16)> {
+    let (host, port) = addr.rsplit_once(':')?;
+    let port = port.parse::<u16>().ok()?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    // 去掉接口后缀，例如 fe80::1%eth0
+    let host = host.split('%').next().unwrap_or(host);
+    // IPv4 映射的 IPv6 地址按 IPv4 显示，便于聚合
+    let host = host.strip_prefix("::ffff:").unwrap_or(host);
+    Some((host.to_string(), port))
+}
+
+// 解析 users:(("sshd",pid=1234,fd=4)) 中第一个进程
+fn parse_ss_process(process_info: &str) -> (String, Option<u32>) {
+    let mut program_name = "Unknown".to_string();
+    let mut pid = None;
+
+    if let Some(start) = process_info.find("((\"")
+        && let Some(end) = process_info[start + 3..].find('"')
+    {
+        program_name = process_info[start + 3..start + 3 + end].to_string();
+    }
+
+    if let Some(pid_start) = process_info.find("pid=") {
+        let rest = &process_info[pid_start + 4..];
+        let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        pid = rest[..end].parse::<u32>().ok();
+    }
+
+    (program_name, pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SS: &str = r#"ESTAB      0      0      192.168.1.10:22      192.168.1.50:51234 users:(("sshd",pid=1234,fd=4))
+LISTEN     0      128    0.0.0.0:80           0.0.0.0:*
+tcp   ESTAB      0      0      [::ffff:192.168.1.10]:443 [::ffff:203.0.113.7]:40000 users:(("nginx",pid=900,fd=12),("nginx",pid=901,fd=12))
+TIME-WAIT  0      0      [fe80::1%eth0]:8080  [fe80::2%eth0]:55555
+ESTAB      0      0      192.168.1.10:22      192.168.1.50:51300 users:(("sshd",pid=1240,fd=4))
+"#;
+
+    const NETSTAT: &str = "Active Internet connections (w/o servers)
+Proto Recv-Q Send-Q Local Address           Foreign Address         State       PID/Program name
+tcp        0      0 192.168.1.10:22         192.168.1.50:51234      ESTABLISHED 1234/sshd
+tcp        0      0 0.0.0.0:80              0.0.0.0:*               LISTEN      900/nginx
+tcp6       0      0 ::1:5432                ::1:40312               TIME_WAIT   -
+";
+
+    #[test]
+    fn parses_ss_output() {
+        let connections = parse_ss_connections(SS);
+        assert_eq!(connections.len(), 4);
+
+        let ssh = &connections[0];
+        assert_eq!(ssh.state, "ESTAB");
+        assert_eq!((ssh.local_address.as_str(), ssh.local_port), ("192.168.1.10", 22));
+        assert_eq!((ssh.remote_address.as_str(), ssh.remote_port), ("192.168.1.50", 51234));
+        assert_eq!((ssh.program.as_str(), ssh.pid), ("sshd", Some(1234)));
+
+        // Netid 列和 IPv4 映射地址
+        let https = &connections[1];
+        assert_eq!(https.remote_address, "203.0.113.7");
+        assert_eq!((https.program.as_str(), https.pid), ("nginx", Some(900)));
+
+        // 去掉接口后缀，没有进程信息
+        let link_local = &connections[2];
+        assert_eq!(link_local.local_address, "fe80::1");
+        assert_eq!((link_local.program.as_str(), link_local.pid), ("Unknown", None));
+    }
+
+    #[test]
+    fn parses_netstat_output() {
+        let connections = parse_netstat_connections(NETSTAT);
+        assert_eq!(connections.len(), 2);
+
+        assert_eq!((connections[0].program.as_str(), connections[0].pid), ("sshd", Some(1234)));
+        assert_eq!(connections[1].state, "TIME_WAIT");
+        assert_eq!((connections[1].local_address.as_str(), connections[1].local_port), ("::1", 5432));
+        assert_eq!(connections[1].program, "Unknown");
+    }
+
+    #[test]
+    fn aggregates_by_remote_host_and_local_port() {
+        let info = aggregate_connections(parse_ss_connections(SS), 2);
+
+        assert_eq!(info.total, 4);
+        // 明细按本地端口排序后截断
+        assert_eq!(info.connections.len(), 2);
+        assert!(info.connections.iter().all(|c| c.local_port == 22));
+
+        let top_host = &info.by_remote_host[0];
+        assert_eq!(top_host.remote_address, "192.168.1.50");
+        assert_eq!(top_host.connection_count, 2);
+        assert_eq!(top_host.local_ports, [22]);
+
+        let top_port = &info.by_local_port[0];
+        assert_eq!(top_port.local_port, 22);
+        assert_eq!(top_port.program, "sshd");
+        assert_eq!(top_port.remote_addresses, ["192.168.1.50"]);
+        assert_eq!(info.by_local_port.len(), 3);
+    }
+
+    #[test]
+    fn resolves_only_remote_addresses() {
+        assert!(is_resolvable_address("203.0.113.7"));
+        assert!(is_resolvable_address("2001:db8::1"));
+        assert!(!is_resolvable_address("127.0.0.1"));
+        assert!(!is_resolvable_address("::"));
+        assert!(!is_resolvable_address("*"));
+
+        assert_eq!(parse_getent_hosts("203.0.113.7     host.example.com alias\n").as_deref(), Some("host.example.com"));
+        assert_eq!(parse_getent_hosts(""), None);
+    }
+}
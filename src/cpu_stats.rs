@@ -0,0 +1,199 @@
+use crate::models::CpuTimeBreakdown;
+use std::fs;
+use std::path::Path;
+
+/// `/proc/stat` 中 "cpu" 行的累计时间（单位 jiffies）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+}
+
+impl CpuTimes {
+    // guest/guest_nice 已经计入 user/nice，不参与求和
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    /// 与上一次采样相比各状态所占的百分比
+    ///
+    /// 部分内核上 iowait 会回退，逐项用 saturating_sub，避免出现巨大的百分比。
+    pub fn breakdown_since(&self, previous: &CpuTimes) -> Option<CpuTimeBreakdown> {
+        let total = self.total().saturating_sub(previous.total());
+        if total == 0 {
+            return None;
+        }
+        let percent = |current: u64, last: u64| current.saturating_sub(last) as f32 * 100.0 / total as f32;
+
+        Some(CpuTimeBreakdown {
+            user: percent(self.user, previous.user),
+            nice: percent(self.nice, previous.nice),
+            system: percent(self.system, previous.system),
+            idle: percent(self.idle, previous.idle),
+            iowait: percent(self.iowait, previous.iowait),
+            irq: percent(self.irq, previous.irq),
+            softirq: percent(self.softirq, previous.softirq),
+            steal: percent(self.steal, previous.steal),
+        })
+    }
+}
+
+/// `/proc/stat` 的汇总数据
+#[derive(Debug, Clone, Default)]
+pub struct ProcStat {
+    pub cpu: CpuTimes,
+    pub context_switches: u64,
+    pub interrupts: u64,
+}
+
+/// 解析 `/proc/stat` 的内容
+pub fn parse_proc_stat(content: &str) -> ProcStat {
+    let mut stat = ProcStat::default();
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("cpu") => {
+                let fields: Vec<u64> = parts.filter_map(|v| v.parse().ok()).collect();
+                let field = |i: usize| fields.get(i).copied().unwrap_or(0);
+                stat.cpu = CpuTimes {
+                    user: field(0),
+                    nice: field(1),
+                    system: field(2),
+                    idle: field(3),
+                    iowait: field(4),
+                    irq: field(5),
+                    softirq: field(6),
+                    steal: field(7),
+                };
+            }
+            Some("ctxt") => stat.context_switches = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0),
+            // "intr <总数> <各中断号计数...>"，只取总数
+            Some("intr") => stat.interrupts = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    stat
+}
+
+/// 读取各核心的当前频率（MHz）和 cpu0 的调频策略
+///
+/// 虚拟机通常没有 cpufreq，此时退回到 `/proc/cpuinfo` 的 "cpu MHz"，调频策略为 None。
+pub fn read_cpu_frequencies(sys_root: &Path, proc_root: &Path) -> (Vec<f32>, Option<String>) {
+    let cpu_dir = sys_root.join("devices/system/cpu");
+    let mut cores: Vec<(u32, f32)> = fs::read_dir(&cpu_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let index = name.strip_prefix("cpu")?.parse::<u32>().ok()?;
+            let khz = fs::read_to_string(entry.path().join("cpufreq/scaling_cur_freq"))
+                .ok()?
+                .trim()
+                .parse::<u64>()
+                .ok()?;
+            Some((index, khz as f32 / 1000.0))
+        })
+        .collect();
+
+    if cores.is_empty() {
+        let frequencies = fs::read_to_string(proc_root.join("cpuinfo"))
+            .map(|content| parse_cpuinfo_mhz(&content))
+            .unwrap_or_default();
+        return (frequencies, None);
+    }
+
+    cores.sort_by_key(|(index, _)| *index);
+    let governor = fs::read_to_string(cpu_dir.join("cpu0/cpufreq/scaling_governor"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    (cores.into_iter().map(|(_, mhz)| mhz).collect(), governor)
+}
+
+fn parse_cpuinfo_mhz(content: &str) -> Vec<f32> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key.trim() != "cpu MHz" {
+                return None;
+            }
+            value.trim().parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PROC_STAT: &str = "cpu  4705 356 584 3699176 23060 0 277 10 5 3
+cpu0 1393 280 290 924737 12350 0 187 5 0 0
+intr 114930548 113 48 0 0 1 0 0
+ctxt 1990473
+btime 1062191376
+processes 2915
+";
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    #[test]
+    fn parses_proc_stat() {
+        let stat = parse_proc_stat(PROC_STAT);
+
+        // 只取汇总的 cpu 行
+        assert_eq!(stat.cpu.user, 4705);
+        assert_eq!(stat.cpu.idle, 3699176);
+        assert_eq!(stat.cpu.steal, 10);
+        assert_eq!(stat.cpu.total(), 4705 + 356 + 584 + 3699176 + 23060 + 277 + 10);
+        assert_eq!(stat.context_switches, 1990473);
+        assert_eq!(stat.interrupts, 114930548);
+    }
+
+    #[test]
+    fn computes_breakdown_between_samples() {
+        let previous = CpuTimes { user: 100, system: 50, idle: 800, iowait: 50, ..Default::default() };
+        let current = CpuTimes { user: 150, system: 70, idle: 820, iowait: 50, steal: 10, ..Default::default() };
+
+        let breakdown = current.breakdown_since(&previous).unwrap();
+        assert_eq!(breakdown.user, 50.0);
+        assert_eq!(breakdown.system, 20.0);
+        assert_eq!(breakdown.idle, 20.0);
+        assert_eq!(breakdown.iowait, 0.0);
+        assert_eq!(breakdown.steal, 10.0);
+        assert!(previous.breakdown_since(&previous).is_none());
+
+        // iowait 回退时该项按 0 处理
+        let regressed = CpuTimes { iowait: 40, ..current };
+        assert_eq!(regressed.breakdown_since(&previous).unwrap().iowait, 0.0);
+    }
+
+    #[test]
+    fn reads_cpufreq_in_core_order() {
+        let (frequencies, governor) = read_cpu_frequencies(&fixture("cpu-sysfs"), &fixture("cpu-proc"));
+
+        assert_eq!(frequencies, [3400.0, 800.0, 2100.5]);
+        assert_eq!(governor.as_deref(), Some("schedutil"));
+    }
+
+    #[test]
+    fn falls_back_to_cpuinfo_without_cpufreq() {
+        let (frequencies, governor) = read_cpu_frequencies(&fixture("missing"), &fixture("cpu-proc"));
+
+        assert_eq!(frequencies, [2399.998, 2400.0]);
+        assert_eq!(governor, None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+/// `/proc/diskstats` 中单个块设备的累计计数器
+#[derive(Debug, Clone, Default)]
+pub struct DiskStatsSample {
+    pub name: String,
+    pub reads_completed: u64,
+    pub sectors_read: u64,
+    pub ms_reading: u64,
+    pub writes_completed: u64,
+    pub sectors_written: u64,
+    pub ms_writing: u64,
+    pub ios_in_progress: u64,
+    pub ms_doing_io: u64,
+    pub weighted_ms_doing_io: u64,
+}
+
+/// diskstats 中的扇区固定为 512 字节，与设备的实际扇区大小无关
+pub const DISKSTATS_SECTOR_SIZE: u64 = 512;
+
+/// 解析 `/proc/diskstats` 的内容
+pub fn parse_diskstats(content: &str) -> Vec<DiskStatsSample> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            // major minor name 之后至少有 11 个字段
+            if parts.len() < 14 {
+                return None;
+            }
+
+            let field = |i: usize| parts.get(i).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+            Some(DiskStatsSample {
+                name: parts[2].to_string(),
+                reads_completed: field(3),
+                sectors_read: field(5),
+                ms_reading: field(6),
+                writes_completed: field(7),
+                sectors_written: field(9),
+                ms_writing: field(10),
+                ios_in_progress: field(11),
+                ms_doing_io: field(12),
+                weighted_ms_doing_io: field(13),
+            })
+        })
+        .collect()
+}
+
+/// 是否为通常不关心的伪块设备（loop、ram、zram）
+pub fn is_pseudo_block_device(name: &str) -> bool {
+    name.starts_with("loop") || name.starts_with("ram") || name.starts_with("zram")
+}
+
+/// 将挂载的设备路径（/dev/sda1、/dev/mapper/root 等）解析为 diskstats 中的设备名
+pub fn resolve_block_device(device_path: &str) -> Option<String> {
+    if !device_path.starts_with("/dev/") {
+        return None;
+    }
+
+    // /dev/mapper/* 和 /dev/disk/by-* 是指向 /dev/dm-N、/dev/sdX 的符号链接
+    let resolved = Path::new(device_path)
+        .canonicalize()
+        .unwrap_or_else(|_| Path::new(device_path).to_path_buf());
+
+    resolved
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// 分区所在的整块磁盘（sda1 -> sda，nvme0n1p2 -> nvme0n1），非分区返回自身
+pub fn parent_block_device(name: &str) -> String {
+    let sys_path = Path::new("/sys/class/block").join(name);
+    if sys_path.join("partition").exists()
+        && let Ok(resolved) = sys_path.canonicalize()
+        && let Some(parent) = resolved.parent().and_then(|p| p.file_name())
+    {
+        return parent.to_string_lossy().to_string();
+    }
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISKSTATS: &str = "   8       0 sda 120345 2301 9876543 45678 65432 1234 5432100 98765 2 123456 144443 0 0 0 0 512 76
+   8       1 sda1 120000 2300 9870000 45600 65400 1230 5430000 98700 0 123400 144300
+ 259       0 nvme0n1 5000 0 800000 1200 3000 0 400000 900 0 1800 2100
+   7       0 loop0 10 0 20 0
+";
+
+    #[test]
+    fn parses_diskstats() {
+        let samples = parse_diskstats(DISKSTATS);
+        // loop0 字段不足，跳过
+        let names: Vec<&str> = samples.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["sda", "sda1", "nvme0n1"]);
+
+        let sda = &samples[0];
+        assert_eq!(sda.reads_completed, 120345);
+        assert_eq!(sda.sectors_read, 9876543);
+        assert_eq!(sda.ms_reading, 45678);
+        assert_eq!(sda.writes_completed, 65432);
+        assert_eq!(sda.sectors_written, 5432100);
+        assert_eq!(sda.ms_writing, 98765);
+        assert_eq!(sda.ios_in_progress, 2);
+        assert_eq!(sda.ms_doing_io, 123456);
+        assert_eq!(sda.weighted_ms_doing_io, 144443);
+    }
+
+    #[test]
+    fn detects_pseudo_devices() {
+        assert!(is_pseudo_block_device("loop3"));
+        assert!(is_pseudo_block_device("zram0"));
+        assert!(is_pseudo_block_device("ram1"));
+        assert!(!is_pseudo_block_device("sda"));
+        assert!(!is_pseudo_block_device("nvme0n1"));
+    }
+
+    #[test]
+    fn resolves_only_device_paths() {
+        assert_eq!(resolve_block_device("tmpfs"), None);
+        assert_eq!(resolve_block_device("server:/export"), None);
+        // 不存在的路径不做解析，直接取文件名
+        assert_eq!(resolve_block_device("/dev/nonexistent-disk9").as_deref(), Some("nonexistent-disk9"));
+    }
+}
@@ -0,0 +1,660 @@
+use crate::collectors::collect_disk_info;
+use crate::handlers::AppState;
+use crate::models::{DiskUsageJob, DiskUsageNode, DiskUsageProgress};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path as StdPath, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::Disks;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// 结果树中每个目录保留的最大子目录数（按大小），其余只计入上级的合计
+const MAX_CHILDREN_PER_NODE: usize = 100;
+
+/// 结果树保留的最大深度，更深的目录只计入上级的合计
+const MAX_TREE_DEPTH: usize = 32;
+
+/// 目录大小分析任务管理
+#[derive(Clone)]
+pub struct DiskUsageJobs {
+    jobs: Arc<RwLock<HashMap<String, JobEntry>>>,
+    cache_ttl: Duration,
+    next_id: Arc<AtomicU64>,
+}
+
+struct JobEntry {
+    job: DiskUsageJob,
+    progress: Arc<ScanProgress>,
+    tree: Option<Arc<DiskUsageNode>>,
+}
+
+/// 扫描线程与 API 共享的进度
+#[derive(Default)]
+struct ScanProgress {
+    files_scanned: AtomicU64,
+    dirs_scanned: AtomicU64,
+    bytes_scanned: AtomicU64,
+    errors: AtomicU64,
+    current_path: Mutex<String>,
+    cancelled: AtomicBool,
+}
+
+impl ScanProgress {
+    fn snapshot(&self) -> DiskUsageProgress {
+        DiskUsageProgress {
+            files_scanned: self.files_scanned.load(Ordering::Relaxed),
+            dirs_scanned: self.dirs_scanned.load(Ordering::Relaxed),
+            bytes_scanned: self.bytes_scanned.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            current_path: self.current_path.lock().map(|p| p.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+impl DiskUsageJobs {
+    pub fn new(cache_ttl: Duration) -> Self {
+        Self {
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+            cache_ttl,
+            next_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// 启动扫描；同一路径已有进行中或未过期的任务时直接复用
+    ///
+    /// 进行中的任务已达 `max_running` 个时不启动新任务，返回 None。
+    pub async fn start(&self, root: PathBuf, refresh: bool, max_running: usize) -> Option<DiskUsageJob> {
+        let root_str = root.to_string_lossy().to_string();
+        let now = chrono::Utc::now().timestamp();
+        let ttl = self.cache_ttl.as_secs() as i64;
+
+        let mut jobs = self.jobs.write().await;
+        // 清理过期的任务
+        jobs.retain(|_, entry| entry.job.finished_at.is_none_or(|finished| now - finished < ttl));
+
+        // 进行中的任务总是复用，已完成的任务在未要求刷新时作为缓存返回
+        if let Some(entry) = jobs.values_mut().find(|e| {
+            e.job.path == root_str
+                && (e.job.status == "running" || (e.job.status == "completed" && !refresh))
+        }) {
+            entry.job.progress = entry.progress.snapshot();
+            let mut job = entry.job.clone();
+            job.cached = job.status == "completed";
+            return Some(job);
+        }
+        if jobs.values().filter(|e| e.job.status == "running").count() >= max_running {
+            warn!("Rejecting disk usage scan for {:?}: {} scans already running", root, max_running);
+            return None;
+        }
+
+        let id = format!("{:x}-{}", now, self.next_id.fetch_add(1, Ordering::Relaxed));
+        let progress = Arc::new(ScanProgress::default());
+        let job = DiskUsageJob {
+            id: id.clone(),
+            path: root_str,
+            status: "running".to_string(),
+            started_at: now,
+            finished_at: None,
+            progress: progress.snapshot(),
+            error: None,
+            cached: false,
+        };
+        jobs.insert(id.clone(), JobEntry {
+            job: job.clone(),
+            progress: progress.clone(),
+            tree: None,
+        });
+        drop(jobs);
+
+        info!("Starting disk usage scan {} for {:?}", id, root);
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let scan_progress = progress.clone();
+            let result = tokio::task::spawn_blocking(move || scan_tree(&root, &scan_progress)).await;
+            manager.finish(&id, result.map_err(|e| e.to_string()).and_then(|r| r)).await;
+        });
+
+        Some(job)
+    }
+
+    async fn finish(&self, id: &str, result: Result<DiskUsageNode, String>) {
+        let mut jobs = self.jobs.write().await;
+        let Some(entry) = jobs.get_mut(id) else {
+            return;
+        };
+
+        entry.job.finished_at = Some(chrono::Utc::now().timestamp());
+        entry.job.progress = entry.progress.snapshot();
+        match result {
+            Ok(tree) => {
+                info!("Disk usage scan {} completed: {} bytes", id, tree.size_bytes);
+                entry.job.status = "completed".to_string();
+                entry.tree = Some(Arc::new(tree));
+            }
+            Err(e) if entry.progress.cancelled.load(Ordering::Relaxed) => {
+                entry.job.status = "cancelled".to_string();
+                entry.job.error = Some(e);
+            }
+            Err(e) => {
+                warn!("Disk usage scan {} failed: {}", id, e);
+                entry.job.status = "failed".to_string();
+                entry.job.error = Some(e);
+            }
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Option<DiskUsageJob> {
+        let jobs = self.jobs.read().await;
+        jobs.get(id).map(|entry| {
+            let mut job = entry.job.clone();
+            job.progress = entry.progress.snapshot();
+            job
+        })
+    }
+
+    pub async fn list(&self) -> Vec<DiskUsageJob> {
+        let jobs = self.jobs.read().await;
+        let mut list: Vec<DiskUsageJob> = jobs.values()
+            .map(|entry| {
+                let mut job = entry.job.clone();
+                job.progress = entry.progress.snapshot();
+                job
+            })
+            .collect();
+        list.sort_by_key(|job| std::cmp::Reverse(job.started_at));
+        list
+    }
+
+    pub async fn tree(&self, id: &str) -> Option<Arc<DiskUsageNode>> {
+        let jobs = self.jobs.read().await;
+        jobs.get(id).and_then(|entry| entry.tree.clone())
+    }
+
+    /// 取消进行中的任务，已结束的任务直接删除
+    pub async fn remove(&self, id: &str) -> bool {
+        let mut jobs = self.jobs.write().await;
+        match jobs.get(id) {
+            Some(entry) if entry.job.status == "running" => {
+                entry.progress.cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            Some(_) => jobs.remove(id).is_some(),
+            None => false,
+        }
+    }
+}
+
+// 扫描逻辑（阻塞，运行在 spawn_blocking 中）
+
+fn scan_tree(root: &StdPath, progress: &ScanProgress) -> Result<DiskUsageNode, String> {
+    let metadata = fs::symlink_metadata(root).map_err(|e| format!("Cannot read {:?}: {}", root, e))?;
+    if !metadata.is_dir() {
+        return Err(format!("{:?} is not a directory", root));
+    }
+
+    let root_dev = metadata.dev();
+    let mut seen_inodes = HashSet::new();
+    // 用显式的栈代替递归，目录层级再深也不会耗尽线程栈
+    let mut stack = vec![PendingDir::open(root.to_path_buf(), 0, progress)];
+
+    while let Some(current) = stack.last_mut() {
+        if progress.cancelled.load(Ordering::Relaxed) {
+            return Err("Scan cancelled".to_string());
+        }
+
+        let Some(path) = current.entries.next() else {
+            // 目录读完，并入上级；根目录读完即得到结果
+            let finished = stack.pop().map(PendingDir::finish);
+            match (finished, stack.last_mut()) {
+                (Some(node), Some(parent)) => parent.add_child(node),
+                (Some(node), None) => return Ok(node),
+                (None, _) => {}
+            }
+            continue;
+        };
+
+        // 不跟随符号链接
+        let Ok(metadata) = path.symlink_metadata() else {
+            progress.errors.fetch_add(1, Ordering::Relaxed);
+            continue;
+        };
+
+        // 只统计同一个文件系统（du -x）
+        if metadata.dev() != root_dev {
+            continue;
+        }
+
+        if metadata.is_dir() {
+            let depth = current.depth + 1;
+            stack.push(PendingDir::open(path, depth, progress));
+        } else {
+            // 硬链接只计算一次
+            if metadata.nlink() > 1 && !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+                continue;
+            }
+            // 按实际占用的块计算，与 du 一致（稀疏文件不会虚高）
+            let size = metadata.blocks() * 512;
+            current.node.size_bytes += size;
+            current.node.file_count += 1;
+            progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+            progress.bytes_scanned.fetch_add(size, Ordering::Relaxed);
+        }
+    }
+    Err(format!("Scan of {:?} ended without a result", root))
+}
+
+/// 扫描中的目录：已统计的部分和尚未处理的目录项
+struct PendingDir {
+    node: DiskUsageNode,
+    entries: std::vec::IntoIter<PathBuf>,
+    depth: usize,
+}
+
+impl PendingDir {
+    fn open(dir: PathBuf, depth: usize, progress: &ScanProgress) -> Self {
+        progress.dirs_scanned.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut current) = progress.current_path.lock() {
+            *current = dir.to_string_lossy().to_string();
+        }
+
+        // 一次读完目录项，不为栈上的每一层各占用一个文件描述符
+        let mut entries = Vec::new();
+        match fs::read_dir(&dir) {
+            Ok(read_dir) => {
+                for entry in read_dir {
+                    match entry {
+                        Ok(entry) => entries.push(entry.path()),
+                        Err(_) => {
+                            progress.errors.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                }
+            }
+            Err(_) => {
+                progress.errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        let node = DiskUsageNode {
+            name: dir.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| dir.to_string_lossy().to_string()),
+            path: dir.to_string_lossy().to_string(),
+            size_bytes: fs::symlink_metadata(&dir).map(|m| m.blocks() * 512).unwrap_or(0),
+            file_count: 0,
+            dir_count: 0,
+            child_count: 0,
+            children: Vec::new(),
+        };
+        Self { node, entries: entries.into_iter(), depth }
+    }
+
+    /// 合计子目录；超出深度或数量上限的子目录只计入合计
+    fn add_child(&mut self, child: DiskUsageNode) {
+        let node = &mut self.node;
+        node.size_bytes += child.size_bytes;
+        node.file_count += child.file_count;
+        node.dir_count += child.dir_count + 1;
+        node.child_count += 1;
+        if self.depth < MAX_TREE_DEPTH {
+            node.children.push(child);
+            // 子目录很多时分批截断，内存只与上限成正比
+            if node.children.len() >= MAX_CHILDREN_PER_NODE * 2 {
+                truncate_children(node);
+            }
+        }
+    }
+
+    fn finish(mut self) -> DiskUsageNode {
+        truncate_children(&mut self.node);
+        self.node
+    }
+}
+
+fn truncate_children(node: &mut DiskUsageNode) {
+    node.children.sort_by_key(|child| std::cmp::Reverse(child.size_bytes));
+    node.children.truncate(MAX_CHILDREN_PER_NODE);
+}
+
+// 截取子树用于返回：限制深度和每层的子目录数量
+fn prune_tree(node: &DiskUsageNode, depth: usize, limit: usize) -> DiskUsageNode {
+    DiskUsageNode {
+        name: node.name.clone(),
+        path: node.path.clone(),
+        size_bytes: node.size_bytes,
+        file_count: node.file_count,
+        dir_count: node.dir_count,
+        child_count: node.child_count,
+        children: if depth == 0 {
+            Vec::new()
+        } else {
+            node.children.iter()
+                .take(limit)
+                .map(|child| prune_tree(child, depth - 1, limit))
+                .collect()
+        },
+    }
+}
+
+fn find_node<'a>(node: &'a DiskUsageNode, path: &StdPath) -> Option<&'a DiskUsageNode> {
+    if StdPath::new(&node.path) == path {
+        return Some(node);
+    }
+    node.children.iter()
+        .filter(|child| path.starts_with(&child.path))
+        .find_map(|child| find_node(child, path))
+}
+
+// API 处理函数
+
+#[derive(Deserialize)]
+pub struct ScanRequest {
+    pub path: String,
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Deserialize)]
+pub struct TreeQuery {
+    pub path: Option<String>,
+    pub depth: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+// 启动扫描：只能扫描磁盘挂载点或配置允许的目录，进行中的任务过多时返回 429
+pub async fn start_scan_handler(
+    State(state): State<AppState>,
+    Json(req): Json<ScanRequest>,
+) -> Result<Json<DiskUsageJob>, StatusCode> {
+    let root = match PathBuf::from(&req.path).canonicalize() {
+        Ok(p) if p.is_dir() => p,
+        Ok(_) => return Err(StatusCode::BAD_REQUEST),
+        Err(_) => return Err(StatusCode::NOT_FOUND),
+    };
+
+    let settings = state.config.read().await.disk_usage.clone();
+    if !is_allowed_root(&root, &settings.allowed_paths).await {
+        warn!("Rejecting disk usage scan for {:?}: not a mount point or allowed path", root);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    state.disk_usage
+        .start(root, req.refresh, settings.max_concurrent_scans)
+        .await
+        .map(Json)
+        .ok_or(StatusCode::TOO_MANY_REQUESTS)
+}
+
+// 挂载点本身，或位于 allowed_paths 中某个目录之下
+async fn is_allowed_root(root: &StdPath, allowed_paths: &[String]) -> bool {
+    let allowed = allowed_paths.iter()
+        .filter_map(|path| PathBuf::from(path).canonicalize().ok())
+        .any(|allowed| root.starts_with(allowed));
+    if allowed {
+        return true;
+    }
+
+    let mount_points = tokio::task::spawn_blocking(|| {
+        collect_disk_info(&Disks::new_with_refreshed_list())
+            .into_iter()
+            .map(|disk| PathBuf::from(disk.mount_point))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    mount_points.iter().any(|mount_point| mount_point == root)
+}
+
+// 任务列表
+pub async fn list_jobs_handler(State(state): State<AppState>) -> Json<Vec<DiskUsageJob>> {
+    Json(state.disk_usage.list().await)
+}
+
+// 任务状态与进度
+pub async fn get_job_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<DiskUsageJob>, StatusCode> {
+    state.disk_usage.get(&job_id).await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+// 取消或删除任务
+pub async fn delete_job_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> StatusCode {
+    if state.disk_usage.remove(&job_id).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+// 获取结果树，可通过 path 逐级下钻
+pub async fn get_tree_handler(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+    Query(query): Query<TreeQuery>,
+) -> Result<Json<DiskUsageNode>, StatusCode> {
+    let tree = match state.disk_usage.tree(&job_id).await {
+        Some(tree) => tree,
+        None if state.disk_usage.get(&job_id).await.is_some() => return Err(StatusCode::CONFLICT),
+        None => return Err(StatusCode::NOT_FOUND),
+    };
+
+    let node = match &query.path {
+        Some(path) => find_node(&tree, StdPath::new(path)).ok_or(StatusCode::NOT_FOUND)?,
+        None => &tree,
+    };
+
+    Ok(Json(prune_tree(node, query.depth.unwrap_or(1), query.limit.unwrap_or(50))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试独立的临时目录，测试结束时删除
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("disk-usage-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn scan(root: &StdPath) -> DiskUsageNode {
+        scan_tree(root, &ScanProgress::default()).unwrap()
+    }
+
+    fn node(path: &str, size_bytes: u64, children: Vec<DiskUsageNode>) -> DiskUsageNode {
+        DiskUsageNode {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.to_string(),
+            size_bytes,
+            file_count: 0,
+            dir_count: children.len() as u64,
+            child_count: children.len(),
+            children,
+        }
+    }
+
+    #[test]
+    fn counts_hardlinks_once() {
+        let dir = TempDir::new("hardlinks");
+        fs::create_dir(dir.0.join("a")).unwrap();
+        fs::create_dir(dir.0.join("b")).unwrap();
+        fs::write(dir.0.join("a/data"), vec![1u8; 64 * 1024]).unwrap();
+        fs::hard_link(dir.0.join("a/data"), dir.0.join("b/data")).unwrap();
+
+        let progress = ScanProgress::default();
+        let tree = scan_tree(&dir.0, &progress).unwrap();
+
+        assert_eq!(tree.file_count, 1);
+        assert_eq!(progress.files_scanned.load(Ordering::Relaxed), 1);
+        let file_size = fs::metadata(dir.0.join("a/data")).unwrap().blocks() * 512;
+        let linked: u64 = tree.children.iter().map(|child| child.size_bytes).sum();
+        let dirs: u64 = ["a", "b"].iter().map(|d| fs::metadata(dir.0.join(d)).unwrap().blocks() * 512).sum();
+        assert_eq!(linked, file_size + dirs);
+    }
+
+    #[test]
+    fn stays_on_one_filesystem() {
+        // /dev 下的 pts、shm 等通常是独立挂载的文件系统
+        let root = StdPath::new("/dev");
+        let root_dev = fs::metadata(root).unwrap().dev();
+        let mounts: Vec<String> = fs::read_dir(root)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.path().symlink_metadata().is_ok_and(|m| m.is_dir() && m.dev() != root_dev))
+            .map(|entry| entry.path().to_string_lossy().to_string())
+            .collect();
+
+        let tree = scan(root);
+        assert!(tree.children.iter().all(|child| !mounts.contains(&child.path)));
+    }
+
+    #[test]
+    fn keeps_largest_children_up_to_limit() {
+        let dir = TempDir::new("wide");
+        let count = MAX_CHILDREN_PER_NODE + 30;
+        for i in 0..count {
+            let sub = dir.0.join(format!("d{:03}", i));
+            fs::create_dir(&sub).unwrap();
+            // 编号越大文件越大
+            fs::write(sub.join("f"), vec![0u8; (i + 1) * 4096]).unwrap();
+        }
+
+        let tree = scan(&dir.0);
+        assert_eq!(tree.child_count, count);
+        assert_eq!(tree.dir_count, count as u64);
+        assert_eq!(tree.file_count, count as u64);
+        assert_eq!(tree.children.len(), MAX_CHILDREN_PER_NODE);
+        assert!(tree.children.windows(2).all(|pair| pair[0].size_bytes >= pair[1].size_bytes));
+        // 被截掉的是最小的目录，但仍计入合计
+        assert!(tree.children.iter().all(|child| child.name.as_str() >= "d030"));
+        assert!(tree.size_bytes > tree.children.iter().map(|child| child.size_bytes).sum::<u64>());
+    }
+
+    #[test]
+    fn limits_tree_depth() {
+        let dir = TempDir::new("deep");
+        let levels = MAX_TREE_DEPTH + 8;
+        let mut path = dir.0.clone();
+        for i in 0..levels {
+            path.push(format!("l{}", i));
+        }
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("f"), b"deep").unwrap();
+
+        let tree = scan(&dir.0);
+        assert_eq!(tree.dir_count, levels as u64);
+        assert_eq!(tree.file_count, 1);
+
+        let mut depth = 0;
+        let mut current = &tree;
+        while let Some(child) = current.children.first() {
+            depth += 1;
+            current = child;
+        }
+        assert_eq!(depth, MAX_TREE_DEPTH);
+        // 最深的保留节点仍包含其下所有内容的合计
+        assert_eq!(current.file_count, 1);
+        assert_eq!(current.child_count, 1);
+    }
+
+    #[test]
+    fn rejects_non_directory_root() {
+        let dir = TempDir::new("file-root");
+        fs::write(dir.0.join("f"), b"x").unwrap();
+
+        assert!(scan_tree(&dir.0.join("f"), &ScanProgress::default()).is_err());
+        assert!(scan_tree(&dir.0.join("missing"), &ScanProgress::default()).is_err());
+    }
+
+    #[test]
+    fn prunes_and_finds_nodes() {
+        let tree = node("/data", 100, vec![
+            node("/data/a", 60, vec![node("/data/a/x", 50, vec![node("/data/a/x/deep", 40, Vec::new())])]),
+            node("/data/b", 30, Vec::new()),
+            node("/data/ab", 10, Vec::new()),
+        ]);
+
+        let pruned = prune_tree(&tree, 1, 2);
+        assert_eq!(pruned.children.len(), 2);
+        assert!(pruned.children.iter().all(|child| child.children.is_empty()));
+        assert_eq!(pruned.child_count, 3);
+
+        let pruned = prune_tree(&tree, 2, 10);
+        assert_eq!(pruned.children[0].children[0].path, "/data/a/x");
+        assert!(pruned.children[0].children[0].children.is_empty());
+
+        assert_eq!(find_node(&tree, StdPath::new("/data/a/x/deep")).map(|n| n.size_bytes), Some(40));
+        // 按路径组件匹配，/data/ab 不会被当作 /data/a 的子目录
+        assert_eq!(find_node(&tree, StdPath::new("/data/ab")).map(|n| n.size_bytes), Some(10));
+        assert!(find_node(&tree, StdPath::new("/data/c")).is_none());
+    }
+
+    #[tokio::test]
+    async fn job_runs_to_completion_and_is_removed() {
+        let dir = TempDir::new("job");
+        fs::write(dir.0.join("f"), vec![0u8; 8192]).unwrap();
+        let jobs = DiskUsageJobs::new(Duration::from_secs(60));
+
+        let job = jobs.start(dir.0.clone(), false, 1).await.unwrap();
+        assert_eq!(job.status, "running");
+        assert!(!job.cached);
+
+        let mut status = job.status.clone();
+        for _ in 0..100 {
+            status = jobs.get(&job.id).await.unwrap().status;
+            if status != "running" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(status, "completed");
+        let tree = jobs.tree(&job.id).await.unwrap();
+        assert_eq!(tree.file_count, 1);
+        assert_eq!(jobs.get(&job.id).await.unwrap().progress.files_scanned, 1);
+
+        // 未要求刷新时复用已完成的结果
+        let cached = jobs.start(dir.0.clone(), false, 1).await.unwrap();
+        assert_eq!(cached.id, job.id);
+        assert!(cached.cached);
+        assert_eq!(jobs.list().await.len(), 1);
+
+        assert!(jobs.remove(&job.id).await);
+        assert!(jobs.get(&job.id).await.is_none());
+        assert!(!jobs.remove(&job.id).await);
+    }
+
+    #[tokio::test]
+    async fn rejects_scans_over_the_limit() {
+        let dir = TempDir::new("limit");
+        let jobs = DiskUsageJobs::new(Duration::from_secs(60));
+
+        assert!(jobs.start(dir.0.clone(), false, 0).await.is_none());
+        assert!(jobs.list().await.is_empty());
+    }
+}
@@ -106,9 +106,101 @@ fn parse_container_json(json: &serde_json::Value, stats_map: &HashMap<String, (f
         memory_percent: memory_percent as f32,
         network_rx_mb,
         network_tx_mb,
+        // 速率由采集器根据两次采样计算
+        network_rx_kbps: 0.0,
+        network_tx_kbps: 0.0,
+        block_read_kbps: 0.0,
+        block_write_kbps: 0.0,
+        gpu_memory_mb: None,
     })
 }
 
+/// 从 `/proc/<pid>/cgroup` 中找出进程所属的 Docker 容器 ID（64 位十六进制）
+///
+/// cgroup v2 为 "0::/system.slice/docker-<id>.scope"，v1 为 "4:memory:/docker/<id>"。
+pub fn container_id_from_cgroup(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let segment = line.rsplit('/').next()?;
+        let id = segment
+            .strip_prefix("docker-")
+            .and_then(|s| s.strip_suffix(".scope"))
+            .unwrap_or(segment);
+        (id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())).then(|| id.to_string())
+    })
+}
+
+/// 解析 `docker inspect --format '{{.Id}} {{.State.Pid}}'` 的输出，返回 (完整 ID, 主进程 PID)，跳过未运行的容器
+pub fn parse_inspect_pids(output: &str) -> Vec<(String, u32)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (id, pid) = line.trim().split_once(' ')?;
+            let pid = pid.trim().parse::<u32>().ok().filter(|pid| *pid > 0)?;
+            Some((id.to_string(), pid))
+        })
+        .collect()
+}
+
+/// 容器网络命名空间的 /proc/<pid>/net/dev 中除 lo 外的收发字节合计
+pub fn parse_net_dev_totals(content: &str) -> Option<(u64, u64)> {
+    let mut totals = None;
+    // 前两行是表头，之后每行为 "名称: rx_bytes rx_packets ... tx_bytes ..."
+    for line in content.lines().skip(2) {
+        let Some((name, fields)) = line.split_once(':') else { continue };
+        if name.trim() == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = fields.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+        if let (Some(rx), Some(tx)) = (fields.first(), fields.get(8)) {
+            let (total_rx, total_tx) = totals.get_or_insert((0, 0));
+            *total_rx += rx;
+            *total_tx += tx;
+        }
+    }
+    totals
+}
+
+/// 容器的块设备 I/O 计数文件，由 /proc/<pid>/cgroup 得出（cgroup v2 的 io.stat 或 v1 的 blkio）
+pub fn block_io_stat_path(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let mut parts = line.splitn(3, ':');
+        let (hierarchy, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+        if hierarchy == "0" && controllers.is_empty() {
+            Some(format!("/sys/fs/cgroup{}/io.stat", path))
+        } else if controllers.split(',').any(|c| c == "blkio") {
+            Some(format!("/sys/fs/cgroup/blkio{}/blkio.throttle.io_service_bytes", path))
+        } else {
+            None
+        }
+    })
+}
+
+/// 解析块设备 I/O 计数文件，返回读写字节合计
+///
+/// cgroup v2 的 io.stat 为 "8:0 rbytes=1 wbytes=2 ..."，
+/// v1 的 blkio.throttle.io_service_bytes 为 "8:0 Read 1"、"8:0 Write 2" 和 "Total 3"。
+pub fn parse_block_io_stat(content: &str) -> (u64, u64) {
+    let (mut read, mut write) = (0u64, 0u64);
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            [_, "Read", value] => read += value.parse::<u64>().unwrap_or(0),
+            [_, "Write", value] => write += value.parse::<u64>().unwrap_or(0),
+            [_, stats @ ..] => {
+                for stat in stats {
+                    match stat.split_once('=') {
+                        Some(("rbytes", value)) => read += value.parse::<u64>().unwrap_or(0),
+                        Some(("wbytes", value)) => write += value.parse::<u64>().unwrap_or(0),
+                        _ => {}
+                    }
+                }
+            }
+            [] => {}
+        }
+    }
+    (read, write)
+}
+
 fn parse_port_mappings(ports_str: &str) -> Vec<PortMapping> {
     let mut mappings = Vec::new();
     
@@ -1,7 +1,8 @@
+use crate::handlers::AppState;
 use crate::models::{DirectoryListing, FileInfo, UploadResponse};
 use axum::{
     body::Body,
-    extract::{Multipart, Path, Query},
+    extract::{Multipart, Path, Query, State},
     http::{header, StatusCode},
     response::{Json, Response},
 };
@@ -18,10 +19,11 @@ pub struct FileQuery {
 
 // 获取文件列表
 pub async fn list_files_handler(
+    State(state): State<AppState>,
     Query(query): Query<FileQuery>,
 ) -> Result<Json<DirectoryListing>, StatusCode> {
-    // 默认根目录，生产环境中应该限制在特定目录
-    let base_path = std::env::var("FILE_STORAGE_PATH").unwrap_or_else(|_| "/home/xianyu/uploads".to_string());
+    // 根目录来自配置 [files] storage_path
+    let base_path = state.config.read().await.files.storage_path().to_string();
     let requested_path = query.path.unwrap_or_else(|| ".".to_string());
     
     // 构建完整路径并确保安全性
@@ -113,10 +115,11 @@ pub async fn list_files_handler(
 
 // 文件上传
 pub async fn upload_file_handler(
+    State(state): State<AppState>,
     Query(query): Query<FileQuery>,
     mut multipart: Multipart,
 ) -> Result<Json<UploadResponse>, StatusCode> {
-    let base_path = std::env::var("FILE_STORAGE_PATH").unwrap_or_else(|_| "/home/xianyu/uploads".to_string());
+    let base_path = state.config.read().await.files.storage_path().to_string();
     let upload_dir = query.path.unwrap_or_else(|| ".".to_string());
     
     // 确保上传目录存在
@@ -186,9 +189,10 @@ pub async fn upload_file_handler(
 
 // 文件下载
 pub async fn download_file_handler(
+    State(state): State<AppState>,
     Path(file_path): Path<String>,
 ) -> Result<Response<Body>, StatusCode> {
-    let base_path = std::env::var("FILE_STORAGE_PATH").unwrap_or_else(|_| "/home/xianyu/uploads".to_string());
+    let base_path = state.config.read().await.files.storage_path().to_string();
     let full_path = PathBuf::from(&base_path).join(&file_path);
     
     // 安全检查
@@ -242,9 +246,10 @@ pub struct CreateDirRequest {
 }
 
 pub async fn create_directory_handler(
+    State(state): State<AppState>,
     Json(req): Json<CreateDirRequest>,
 ) -> Result<Json<UploadResponse>, StatusCode> {
-    let base_path = std::env::var("FILE_STORAGE_PATH").unwrap_or_else(|_| "/home/xianyu/uploads".to_string());
+    let base_path = state.config.read().await.files.storage_path().to_string();
     let dir_path = PathBuf::from(&base_path).join(&req.path).join(&req.name);
     
     // 安全检查
@@ -280,9 +285,10 @@ pub struct DeleteRequest {
 }
 
 pub async fn delete_file_handler(
+    State(state): State<AppState>,
     Json(req): Json<DeleteRequest>,
 ) -> Result<Json<UploadResponse>, StatusCode> {
-    let base_path = std::env::var("FILE_STORAGE_PATH").unwrap_or_else(|_| "/home/xianyu/uploads".to_string());
+    let base_path = state.config.read().await.files.storage_path().to_string();
     let file_path = PathBuf::from(&base_path).join(&req.path);
     
     // 安全检查
@@ -0,0 +1,163 @@
+use super::fdinfo::{processes_for_device, read_drm_clients};
+use super::{DrmCard, GpuBackend, find_hwmon_dir, gpu_id, list_drm_cards, read_trimmed, read_u64};
+use crate::models::GpuInfo;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const AMD_VENDOR_ID: &str = "0x1002";
+
+/// 通过 amdgpu 驱动的 sysfs 接口采集 AMD GPU
+pub struct AmdGpuBackend {
+    sys_root: PathBuf,
+    proc_root: PathBuf,
+}
+
+impl AmdGpuBackend {
+    pub fn new(sys_root: &Path, proc_root: &Path) -> Self {
+        Self {
+            sys_root: sys_root.to_path_buf(),
+            proc_root: proc_root.to_path_buf(),
+        }
+    }
+
+    fn read_cards(&self) -> Vec<GpuInfo> {
+        let cards: Vec<DrmCard> = list_drm_cards(&self.sys_root, AMD_VENDOR_ID)
+            .into_iter()
+            .filter(|card| card.driver == "amdgpu")
+            .collect();
+        if cards.is_empty() {
+            return Vec::new();
+        }
+
+        let clients = read_drm_clients(&self.proc_root, &["amdgpu"]);
+        cards
+            .iter()
+            .map(|card| GpuInfo {
+                processes: processes_for_device(&clients, &card.pci_slot),
+                ..read_card(card)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl GpuBackend for AmdGpuBackend {
+    fn vendor(&self) -> &'static str {
+        "amd"
+    }
+
+    async fn collect(&self, _command_timeout: Duration) -> Option<Vec<GpuInfo>> {
+        let gpus = self.read_cards();
+        if gpus.is_empty() { None } else { Some(gpus) }
+    }
+}
+
+fn read_card(card: &DrmCard) -> GpuInfo {
+    let device = &card.device_dir;
+    let hwmon = find_hwmon_dir(device);
+    let hwmon_u64 = |file: &str| hwmon.as_ref().and_then(|dir| read_u64(&dir.join(file)));
+
+    let vram_total = read_u64(&device.join("mem_info_vram_total"));
+    let vram_used = read_u64(&device.join("mem_info_vram_used"));
+
+    // 风扇转速以 PWM 占空比表示，pwm1_max 缺省为 255
+    let fan_speed_percent = hwmon_u64("pwm1").map(|pwm| {
+        let max = hwmon_u64("pwm1_max").filter(|m| *m > 0).unwrap_or(255);
+        (pwm * 100 / max).min(100) as u8
+    });
+
+    let pci_bus_id = Some(card.pci_slot.clone()).filter(|slot| !slot.is_empty());
+    GpuInfo {
+        id: gpu_id("amd", pci_bus_id.as_deref(), card.index),
+        vendor: "amd".to_string(),
+        name: read_trimmed(&device.join("product_name"))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("AMD Radeon [{}]", card.pci_id)),
+        index: card.index,
+        pci_bus_id,
+        memory_total_mb: vram_total.map(|total| (total / 1024 / 1024) as u32),
+        memory_used_mb: vram_used.map(|used| (used / 1024 / 1024) as u32),
+        memory_free_mb: vram_total
+            .zip(vram_used)
+            .map(|(total, used)| (total.saturating_sub(used) / 1024 / 1024) as u32),
+        utilization_percent: read_u64(&device.join("gpu_busy_percent")).map(|busy| busy.min(100) as u8),
+        // temp1 为 edge 温度（毫摄氏度）
+        temperature_celsius: hwmon_u64("temp1_input").map(|t| (t / 1000).min(255) as u8),
+        // 功耗单位为微瓦，较新内核以 power1_input 取代 power1_average
+        power_draw_watts: hwmon_u64("power1_average")
+            .or_else(|| hwmon_u64("power1_input"))
+            .map(|uw| uw as f32 / 1_000_000.0),
+        power_limit_watts: hwmon_u64("power1_cap").map(|uw| uw as f32 / 1_000_000.0),
+        fan_speed_percent,
+        graphics_clock_mhz: read_dpm_clock(&device.join("pp_dpm_sclk"))
+            .or_else(|| hwmon_u64("freq1_input").map(|hz| (hz / 1_000_000) as u32)),
+        memory_clock_mhz: read_dpm_clock(&device.join("pp_dpm_mclk"))
+            .or_else(|| hwmon_u64("freq2_input").map(|hz| (hz / 1_000_000) as u32)),
+        encoder_utilization_percent: None,
+        decoder_utilization_percent: None,
+        throttle_reasons: Vec::new(),
+        pcie: None,
+        ecc_errors: None,
+        processes: Vec::new(),
+    }
+}
+
+// pp_dpm_sclk 中带 '*' 的一行为当前频率："1: 2100Mhz *"
+fn read_dpm_clock(path: &Path) -> Option<u32> {
+    read_trimmed(path)?
+        .lines()
+        .find(|line| line.trim_end().ends_with('*'))?
+        .split_whitespace()
+        .nth(1)?
+        .to_lowercase()
+        .strip_suffix("mhz")?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::fixture_root;
+
+    #[test]
+    fn reads_amdgpu_card_from_sysfs() {
+        let gpus = AmdGpuBackend::new(&fixture_root("gpu-sysfs"), &fixture_root("gpu-proc")).read_cards();
+
+        assert_eq!(gpus.len(), 1);
+        let gpu = &gpus[0];
+        assert_eq!(gpu.vendor, "amd");
+        assert_eq!(gpu.name, "AMD Radeon RX 6800 XT");
+        assert_eq!(gpu.index, 0);
+        assert_eq!(gpu.id, "amd:0000:03:00.0");
+        assert_eq!(gpu.memory_total_mb, Some(16368));
+        assert_eq!(gpu.memory_used_mb, Some(1024));
+        assert_eq!(gpu.memory_free_mb, Some(15344));
+        assert_eq!(gpu.utilization_percent, Some(37));
+        assert_eq!(gpu.temperature_celsius, Some(52));
+        assert_eq!(gpu.power_draw_watts, Some(48.0));
+        assert_eq!(gpu.power_limit_watts, Some(255.0));
+        assert_eq!(gpu.fan_speed_percent, Some(40));
+        assert_eq!(gpu.graphics_clock_mhz, Some(2105));
+        assert_eq!(gpu.memory_clock_mhz, Some(1000));
+    }
+
+    #[test]
+    fn attributes_vram_to_processes_via_fdinfo() {
+        let gpus = AmdGpuBackend::new(&fixture_root("gpu-sysfs"), &fixture_root("gpu-proc")).read_cards();
+        let processes = &gpus[0].processes;
+
+        // 1234 有两个描述符指向同一客户端，只统计一次；5678 是 Intel 客户端
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, 1234);
+        assert_eq!(processes[0].name, "python3");
+        assert_eq!(processes[0].used_memory_mb, Some(2048));
+        assert_eq!(processes[0].process_type, "compute");
+    }
+
+    #[test]
+    fn returns_nothing_without_drm_class() {
+        assert!(AmdGpuBackend::new(&fixture_root("missing"), &fixture_root("missing")).read_cards().is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::models::GpuProcess;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// 通过 `/proc/<pid>/fdinfo` 中的 DRM 客户端统计得到的进程 GPU 占用
+///
+/// amdgpu、i915、xe 都按照内核的 drm-usage-stats 格式输出：
+///
+/// ```text
+/// drm-driver:       amdgpu
+/// drm-pdev:         0000:03:00.0
+/// drm-client-id:    42
+/// drm-memory-vram:  1048576 KiB
+/// drm-engine-gfx:   123456789 ns
+/// ```
+#[derive(Debug, Clone)]
+pub struct DrmClient {
+    pub pid: u32,
+    pub name: String,
+    pub pdev: String,
+    pub memory_kb: Option<u64>,
+    pub compute: bool,
+}
+
+// 按优先级取第一个存在的显存字段：amdgpu 旧格式、通用 vram、Intel 独显、Intel 核显
+const MEMORY_KEYS: &[&str] = &[
+    "drm-memory-vram",
+    "drm-resident-vram0",
+    "drm-resident-local0",
+    "drm-resident-system0",
+];
+
+/// 扫描 `<root>/<pid>/fd` 中指向 /dev/dri 的描述符，返回指定驱动的客户端
+pub fn read_drm_clients(proc_root: &Path, drivers: &[&str]) -> Vec<DrmClient> {
+    let mut clients = Vec::new();
+
+    for entry in fs::read_dir(proc_root).into_iter().flatten().flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let process_dir = entry.path();
+        // 同一客户端可能被 dup 到多个描述符，按 drm-client-id 去重
+        let mut seen = HashSet::new();
+
+        for fd in fs::read_dir(process_dir.join("fd")).into_iter().flatten().flatten() {
+            let is_dri = fs::read_link(fd.path())
+                .map(|target| target.starts_with("/dev/dri"))
+                .unwrap_or(false);
+            if !is_dri {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(process_dir.join("fdinfo").join(fd.file_name())) else {
+                continue;
+            };
+            let fields = parse_fdinfo(&content);
+            let Some(driver) = fields.get("drm-driver") else {
+                continue;
+            };
+            if !drivers.contains(&driver.as_str()) {
+                continue;
+            }
+            let client_id = fields.get("drm-client-id").cloned().unwrap_or_default();
+            if !seen.insert(client_id) {
+                continue;
+            }
+
+            clients.push(DrmClient {
+                pid,
+                name: fs::read_to_string(process_dir.join("comm"))
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_default(),
+                pdev: fields.get("drm-pdev").cloned().unwrap_or_default(),
+                memory_kb: MEMORY_KEYS
+                    .iter()
+                    .find_map(|key| fields.get(*key))
+                    .and_then(|value| parse_kib(value)),
+                compute: fields
+                    .get("drm-engine-compute")
+                    .and_then(|value| value.split_whitespace().next()?.parse::<u64>().ok())
+                    .is_some_and(|ns| ns > 0),
+            });
+        }
+    }
+
+    clients
+}
+
+/// 将同一进程在某块卡上的客户端合并为一条 GpuProcess
+pub fn processes_for_device(clients: &[DrmClient], pdev: &str) -> Vec<GpuProcess> {
+    let mut by_pid: HashMap<u32, GpuProcess> = HashMap::new();
+
+    for client in clients.iter().filter(|c| c.pdev == pdev) {
+        let process = by_pid.entry(client.pid).or_insert_with(|| GpuProcess {
+            pid: client.pid,
+            name: client.name.clone(),
+            used_memory_mb: None,
+            process_type: "graphics".to_string(),
+            container_id: None,
+            container_name: None,
+        });
+        if let Some(kb) = client.memory_kb {
+            process.used_memory_mb = Some(process.used_memory_mb.unwrap_or(0) + (kb / 1024) as u32);
+        }
+        if client.compute {
+            process.process_type = "compute".to_string();
+        }
+    }
+
+    let mut processes: Vec<GpuProcess> = by_pid.into_values().collect();
+    processes.sort_by_key(|p| p.pid);
+    processes
+}
+
+fn parse_fdinfo(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+// "1048576 KiB"，部分驱动省略单位时按字节处理
+fn parse_kib(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let number: u64 = parts.next()?.parse().ok()?;
+    Some(match parts.next() {
+        Some("KiB") => number,
+        Some("MiB") => number * 1024,
+        Some("GiB") => number * 1024 * 1024,
+        _ => number / 1024,
+    })
+}
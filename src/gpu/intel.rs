@@ -0,0 +1,157 @@
+use super::fdinfo::{processes_for_device, read_drm_clients};
+use super::{DrmCard, GpuBackend, find_hwmon_dir, gpu_id, list_drm_cards, read_u64};
+use crate::collector_utils::RateTracker;
+use crate::models::GpuInfo;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const INTEL_VENDOR_ID: &str = "0x8086";
+
+/// 通过 i915 / xe 驱动的 sysfs 接口采集 Intel GPU
+///
+/// 两个驱动都不在 sysfs 中提供利用率和显存占用，这两项为空；
+/// 独显的功耗由 hwmon 的 energy1_input 计数器换算。
+pub struct IntelGpuBackend {
+    sys_root: PathBuf,
+    proc_root: PathBuf,
+    energy_rates: Mutex<RateTracker>,
+}
+
+impl IntelGpuBackend {
+    pub fn new(sys_root: &Path, proc_root: &Path) -> Self {
+        Self {
+            sys_root: sys_root.to_path_buf(),
+            proc_root: proc_root.to_path_buf(),
+            energy_rates: Mutex::new(RateTracker::new()),
+        }
+    }
+
+    fn read_cards(&self, now: Instant) -> Vec<GpuInfo> {
+        let cards: Vec<DrmCard> = list_drm_cards(&self.sys_root, INTEL_VENDOR_ID)
+            .into_iter()
+            .filter(|card| card.driver == "i915" || card.driver == "xe")
+            .collect();
+        if cards.is_empty() {
+            return Vec::new();
+        }
+
+        let clients = read_drm_clients(&self.proc_root, &["i915", "xe"]);
+        cards
+            .iter()
+            .map(|card| GpuInfo {
+                processes: processes_for_device(&clients, &card.pci_slot),
+                ..self.read_card(card, now)
+            })
+            .collect()
+    }
+
+    fn read_card(&self, card: &DrmCard, now: Instant) -> GpuInfo {
+        let hwmon = find_hwmon_dir(&card.device_dir);
+        let hwmon_u64 = |file: &str| hwmon.as_ref().and_then(|dir| read_u64(&dir.join(file)));
+
+        // 微焦耳计数器的每秒增量即为微瓦
+        let power_draw_watts = hwmon_u64("energy1_input").and_then(|energy_uj| {
+            let mut rates = self.energy_rates.lock().unwrap_or_else(|e| e.into_inner());
+            rates
+                .rate(&format!("card{}", card.index), energy_uj, now)
+                .map(|uw| (uw / 1_000_000.0) as f32)
+        });
+
+        // i915 在 card 目录下提供 gt 频率，xe 按 tile/gt 划分
+        let current_freq = if card.driver == "xe" {
+            read_u64(&card.device_dir.join("tile0/gt0/freq0/cur_freq"))
+        } else {
+            read_u64(&card.card_dir.join("gt_cur_freq_mhz"))
+                .or_else(|| read_u64(&card.card_dir.join("gt/gt0/rps_cur_freq_mhz")))
+        };
+
+        let pci_bus_id = Some(card.pci_slot.clone()).filter(|slot| !slot.is_empty());
+        GpuInfo {
+            id: gpu_id("intel", pci_bus_id.as_deref(), card.index),
+            vendor: "intel".to_string(),
+            name: format!("Intel Graphics [{}]", card.pci_id),
+            index: card.index,
+            pci_bus_id,
+            memory_total_mb: None,
+            memory_used_mb: None,
+            memory_free_mb: None,
+            utilization_percent: None,
+            temperature_celsius: hwmon_u64("temp1_input")
+                .or_else(|| hwmon_u64("temp2_input"))
+                .map(|t| (t / 1000).min(255) as u8),
+            power_draw_watts,
+            power_limit_watts: hwmon_u64("power1_max")
+                .filter(|uw| *uw > 0)
+                .map(|uw| uw as f32 / 1_000_000.0),
+            fan_speed_percent: None,
+            graphics_clock_mhz: current_freq.map(|mhz| mhz as u32),
+            memory_clock_mhz: None,
+            encoder_utilization_percent: None,
+            decoder_utilization_percent: None,
+            throttle_reasons: Vec::new(),
+            pcie: None,
+            ecc_errors: None,
+            processes: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl GpuBackend for IntelGpuBackend {
+    fn vendor(&self) -> &'static str {
+        "intel"
+    }
+
+    async fn collect(&self, _command_timeout: Duration) -> Option<Vec<GpuInfo>> {
+        let gpus = self.read_cards(Instant::now());
+        if gpus.is_empty() { None } else { Some(gpus) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::fixture_root;
+    use std::time::Duration;
+
+    #[test]
+    fn reads_i915_and_xe_cards_from_sysfs() {
+        let backend = IntelGpuBackend::new(&fixture_root("gpu-sysfs"), &fixture_root("gpu-proc"));
+        let gpus = backend.read_cards(Instant::now());
+
+        assert_eq!(gpus.len(), 2);
+        let (i915, xe) = (&gpus[0], &gpus[1]);
+        assert_eq!(i915.vendor, "intel");
+        assert_eq!(i915.index, 1);
+        assert_eq!(i915.id, "intel:0000:00:02.0");
+        assert_eq!(i915.name, "Intel Graphics [0x46a6]");
+        assert_eq!(i915.graphics_clock_mhz, Some(350));
+        assert_eq!(i915.power_draw_watts, None);
+        assert_eq!(i915.utilization_percent, None);
+
+        assert_eq!(xe.index, 2);
+        assert_eq!(xe.id, "intel:0000:04:00.0");
+        assert_eq!(xe.graphics_clock_mhz, Some(1800));
+        assert_eq!(xe.temperature_celsius, Some(61));
+        assert_eq!(xe.power_limit_watts, Some(190.0));
+
+        assert_eq!(i915.processes.len(), 1);
+        assert_eq!(i915.processes[0].name, "jellyfin-ffmpeg");
+        assert_eq!(i915.processes[0].used_memory_mb, Some(300));
+        assert_eq!(i915.processes[0].process_type, "graphics");
+        assert!(xe.processes.is_empty());
+    }
+
+    #[test]
+    fn derives_power_from_energy_counter() {
+        let backend = IntelGpuBackend::new(&fixture_root("gpu-sysfs"), &fixture_root("gpu-proc"));
+        let start = Instant::now();
+        backend.read_cards(start);
+
+        // 夹具中的计数器不变，第二次采样得到 0 W
+        let gpus = backend.read_cards(start + Duration::from_secs(1));
+        assert_eq!(gpus[1].power_draw_watts, Some(0.0));
+    }
+}
@@ -0,0 +1,110 @@
+mod amdgpu;
+mod fdinfo;
+mod intel;
+mod nvidia;
+
+pub use amdgpu::AmdGpuBackend;
+pub use intel::IntelGpuBackend;
+pub use nvidia::NvidiaBackend;
+
+use crate::models::GpuInfo;
+use async_trait::async_trait;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// GPU 数据来源，每个厂商一个实现
+#[async_trait]
+pub trait GpuBackend: Send + Sync {
+    /// 厂商标识，与 `GpuInfo::vendor` 一致
+    fn vendor(&self) -> &'static str;
+
+    /// 采集本厂商的所有 GPU，没有对应设备或工具时返回 None；外部命令不超过 `command_timeout`
+    async fn collect(&self, command_timeout: Duration) -> Option<Vec<GpuInfo>>;
+}
+
+/// 默认启用的全部后端
+pub fn default_backends() -> Vec<Box<dyn GpuBackend>> {
+    let (sys_root, proc_root) = (Path::new("/sys"), Path::new("/proc"));
+    vec![
+        Box::new(NvidiaBackend),
+        Box::new(AmdGpuBackend::new(sys_root, proc_root)),
+        Box::new(IntelGpuBackend::new(sys_root, proc_root)),
+    ]
+}
+
+/// 跨厂商唯一的 GPU 标识 `vendor:pci_bus_id`，没有 PCI 地址时退回 `vendor:index`
+pub fn gpu_id(vendor: &str, pci_bus_id: Option<&str>, index: u32) -> String {
+    match pci_bus_id {
+        Some(pci_bus_id) => format!("{}:{}", vendor, pci_bus_id),
+        None => format!("{}:{}", vendor, index),
+    }
+}
+
+/// `<root>/class/drm/cardN` 对应的显卡
+struct DrmCard {
+    index: u32,
+    card_dir: PathBuf,
+    device_dir: PathBuf,
+    driver: String,
+    pci_id: String,
+    pci_slot: String,
+}
+
+/// 列出属于指定 PCI 厂商 ID 的 DRM 显卡（跳过 card0-DP-1 这类连接器目录）
+fn list_drm_cards(sys_root: &Path, vendor_id: &str) -> Vec<DrmCard> {
+    let mut cards: Vec<DrmCard> = fs::read_dir(sys_root.join("class/drm"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let index = name.strip_prefix("card")?.parse::<u32>().ok()?;
+            let card_dir = entry.path();
+            let device_dir = card_dir.join("device");
+            if read_trimmed(&device_dir.join("vendor"))? != vendor_id {
+                return None;
+            }
+            // uevent 中的 DRIVER=amdgpu / i915 / xe 和 PCI_SLOT_NAME=0000:03:00.0
+            let uevent = read_trimmed(&device_dir.join("uevent"))?;
+            let uevent_field = |key: &str| {
+                uevent.lines().find_map(|line| line.strip_prefix(key)).map(|v| v.to_string())
+            };
+            let driver = uevent_field("DRIVER=")?;
+            Some(DrmCard {
+                index,
+                pci_id: read_trimmed(&device_dir.join("device")).unwrap_or_default(),
+                pci_slot: uevent_field("PCI_SLOT_NAME=").unwrap_or_default(),
+                card_dir,
+                device_dir,
+                driver,
+            })
+        })
+        .collect();
+    cards.sort_by_key(|card| card.index);
+    cards
+}
+
+/// 设备目录下第一个 hwmon 目录
+fn find_hwmon_dir(device_dir: &Path) -> Option<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(device_dir.join("hwmon"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort();
+    dirs.into_iter().next()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+#[cfg(test)]
+fn fixture_root(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
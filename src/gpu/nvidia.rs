@@ -0,0 +1,323 @@
+use super::{GpuBackend, gpu_id};
+use crate::collector_utils::execute_command_with_timeout;
+use crate::models::{EccErrorCounts, GpuInfo, GpuProcess, PcieLinkInfo};
+use async_trait::async_trait;
+use roxmltree::{Document, Node, ParsingOptions};
+use std::time::Duration;
+use tracing::debug;
+
+/// 通过 `nvidia-smi -q -x` 采集 NVIDIA GPU
+///
+/// XML 输出按字段名取值，不受 GPU 名称中逗号的影响，
+/// 并且一次调用即可拿到降频原因、PCIe、ECC 和进程列表。
+pub struct NvidiaBackend;
+
+#[async_trait]
+impl GpuBackend for NvidiaBackend {
+    fn vendor(&self) -> &'static str {
+        "nvidia"
+    }
+
+    async fn collect(&self, command_timeout: Duration) -> Option<Vec<GpuInfo>> {
+        match execute_command_with_timeout("nvidia-smi", &["-q", "-x"], command_timeout).await {
+            Ok(output) => parse_nvidia_smi_xml(&output),
+            Err(e) => {
+                debug!("nvidia-smi failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// 解析 `nvidia-smi -q -x` 的输出
+pub fn parse_nvidia_smi_xml(output: &str) -> Option<Vec<GpuInfo>> {
+    // 输出带有 <!DOCTYPE nvidia_smi_log SYSTEM "nvsmi_device_vN.dtd">
+    let options = ParsingOptions { allow_dtd: true, ..ParsingOptions::default() };
+    let document = match Document::parse_with_options(output, options) {
+        Ok(document) => document,
+        Err(e) => {
+            debug!("Failed to parse nvidia-smi XML: {}", e);
+            return None;
+        }
+    };
+
+    // nvidia-smi 的 index 即 <gpu> 元素的枚举顺序
+    let gpus: Vec<GpuInfo> = document
+        .root_element()
+        .children()
+        .filter(|node| node.has_tag_name("gpu"))
+        .enumerate()
+        .map(|(index, gpu)| parse_gpu(index as u32, gpu))
+        .collect();
+
+    if gpus.is_empty() { None } else { Some(gpus) }
+}
+
+fn parse_gpu(index: u32, gpu: Node) -> GpuInfo {
+    let memory = |field: &str| measurement(gpu, &["fb_memory_usage", field], "MiB").map(|v| v as u32);
+    let percent = |path: &[&str]| measurement(gpu, path, "%").map(|v| v.min(100.0) as u8);
+    // 530 版本驱动起功耗移到 gpu_power_readings，字段名也有变化
+    let power = |fields: &[&str]| {
+        ["gpu_power_readings", "power_readings"].iter().find_map(|section| {
+            fields.iter().find_map(|field| measurement(gpu, &[section, field], "W").map(|v| v as f32))
+        })
+    };
+
+    let pci_bus_id = text(gpu, &["pci", "pci_bus_id"])
+        .or_else(|| gpu.attribute("id"))
+        .map(|id| id.to_string());
+    GpuInfo {
+        id: gpu_id("nvidia", pci_bus_id.as_deref(), index),
+        vendor: "nvidia".to_string(),
+        name: text(gpu, &["product_name"]).unwrap_or("Unknown").to_string(),
+        index,
+        pci_bus_id,
+        memory_total_mb: memory("total"),
+        memory_used_mb: memory("used"),
+        memory_free_mb: memory("free"),
+        utilization_percent: percent(&["utilization", "gpu_util"]),
+        temperature_celsius: measurement(gpu, &["temperature", "gpu_temp"], "C").map(|v| v.clamp(0.0, 255.0) as u8),
+        power_draw_watts: power(&["power_draw", "instant_power_draw", "average_power_draw"]),
+        power_limit_watts: power(&["current_power_limit", "power_limit", "enforced_power_limit"]),
+        fan_speed_percent: percent(&["fan_speed"]),
+        graphics_clock_mhz: measurement(gpu, &["clocks", "graphics_clock"], "MHz").map(|v| v as u32),
+        memory_clock_mhz: measurement(gpu, &["clocks", "mem_clock"], "MHz").map(|v| v as u32),
+        encoder_utilization_percent: percent(&["utilization", "encoder_util"]),
+        decoder_utilization_percent: percent(&["utilization", "decoder_util"]),
+        throttle_reasons: parse_throttle_reasons(gpu),
+        pcie: parse_pcie_link(gpu),
+        ecc_errors: parse_ecc_errors(gpu),
+        processes: parse_processes(gpu),
+    }
+}
+
+// 新驱动为 clocks_event_reasons，旧驱动为 clocks_throttle_reasons
+fn parse_throttle_reasons(gpu: Node) -> Vec<String> {
+    let Some(section) = child(gpu, "clocks_event_reasons").or_else(|| child(gpu, "clocks_throttle_reasons")) else {
+        return Vec::new();
+    };
+
+    section
+        .children()
+        .filter(|node| node.is_element() && node.text().map(str::trim) == Some("Active"))
+        .map(|node| {
+            let name = node.tag_name().name();
+            name.strip_prefix("clocks_event_reason_")
+                .or_else(|| name.strip_prefix("clocks_throttle_reason_"))
+                .unwrap_or(name)
+                .to_string()
+        })
+        .collect()
+}
+
+fn parse_pcie_link(gpu: Node) -> Option<PcieLinkInfo> {
+    let link = find(gpu, &["pci", "pci_gpu_link_info"])?;
+    let generation = |field: &str| text(link, &["pcie_gen", field])?.parse::<u8>().ok();
+    // 宽度形如 "16x"
+    let width = |field: &str| text(link, &["link_widths", field])?.strip_suffix('x')?.parse::<u8>().ok();
+
+    let info = PcieLinkInfo {
+        generation: generation("current_link_gen"),
+        max_generation: generation("max_link_gen"),
+        width: width("current_link_width"),
+        max_width: width("max_link_width"),
+    };
+    if info.generation.is_none() && info.width.is_none() {
+        return None;
+    }
+    Some(info)
+}
+
+/// 未开启 ECC 的显卡全部为 N/A，返回 None
+fn parse_ecc_errors(gpu: Node) -> Option<EccErrorCounts> {
+    let ecc = child(gpu, "ecc_errors")?;
+    let (volatile_corrected, volatile_uncorrected) = sum_ecc_counters(child(ecc, "volatile")?)?;
+    let (aggregate_corrected, aggregate_uncorrected) = child(ecc, "aggregate")
+        .and_then(sum_ecc_counters)
+        .unwrap_or((0, 0));
+
+    Some(EccErrorCounts {
+        volatile_corrected,
+        volatile_uncorrected,
+        aggregate_corrected,
+        aggregate_uncorrected,
+    })
+}
+
+// 旧格式：single_bit/total、double_bit/total；Ampere 之后：sram_correctable、dram_uncorrectable ...
+fn sum_ecc_counters(section: Node) -> Option<(u64, u64)> {
+    let mut corrected = None;
+    let mut uncorrected = None;
+    let add = |slot: &mut Option<u64>, value: Option<u64>| {
+        if let Some(value) = value {
+            *slot = Some(slot.unwrap_or(0) + value);
+        }
+    };
+
+    for node in section.children().filter(|n| n.is_element()) {
+        let name = node.tag_name().name();
+        let value = || node.text().and_then(|t| t.trim().parse::<u64>().ok());
+        match name {
+            "single_bit" => add(&mut corrected, text(node, &["total"]).and_then(|t| t.parse().ok())),
+            "double_bit" => add(&mut uncorrected, text(node, &["total"]).and_then(|t| t.parse().ok())),
+            _ if name.contains("uncorrectable") => add(&mut uncorrected, value()),
+            _ if name.contains("correctable") => add(&mut corrected, value()),
+            _ => {}
+        }
+    }
+
+    if corrected.is_none() && uncorrected.is_none() {
+        return None;
+    }
+    Some((corrected.unwrap_or(0), uncorrected.unwrap_or(0)))
+}
+
+fn parse_processes(gpu: Node) -> Vec<GpuProcess> {
+    let Some(processes) = child(gpu, "processes") else {
+        return Vec::new();
+    };
+
+    processes
+        .children()
+        .filter(|node| node.has_tag_name("process_info"))
+        .filter_map(|process| {
+            let name = text(process, &["process_name"]).unwrap_or("");
+            Some(GpuProcess {
+                pid: text(process, &["pid"])?.parse().ok()?,
+                name: name.rsplit('/').next().unwrap_or(name).to_string(),
+                // 容器或 WDDM 模式下显存为 N/A
+                used_memory_mb: measurement(process, &["used_memory"], "MiB").map(|v| v as u32),
+                process_type: match text(process, &["type"]) {
+                    Some("C") => "compute",
+                    Some("G") => "graphics",
+                    Some("C+G") => "compute+graphics",
+                    _ => "unknown",
+                }
+                .to_string(),
+                container_id: None,
+                container_name: None,
+            })
+        })
+        .collect()
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn find<'a, 'input>(node: Node<'a, 'input>, path: &[&str]) -> Option<Node<'a, 'input>> {
+    path.iter().try_fold(node, |node, name| child(node, name))
+}
+
+fn text<'a>(node: Node<'a, '_>, path: &[&str]) -> Option<&'a str> {
+    find(node, path)?.text().map(str::trim).filter(|t| !t.is_empty())
+}
+
+/// 读取带单位的数值（"24576 MiB"、"35 C"），N/A 或单位不符时返回 None
+fn measurement(node: Node, path: &[&str], unit: &str) -> Option<f64> {
+    let raw = text(node, path)?;
+    let (value, actual_unit) = raw.split_once(' ')?;
+    if actual_unit.trim() != unit {
+        debug!("Unexpected unit in nvidia-smi {}: {:?} (expected {})", path.join("/"), raw, unit);
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_GPU: &str = include_str!("../../tests/fixtures/nvidia-smi/multi_gpu.xml");
+    const NOT_AVAILABLE: &str = include_str!("../../tests/fixtures/nvidia-smi/not_available.xml");
+
+    #[test]
+    fn parses_every_gpu_by_field_name() {
+        let gpus = parse_nvidia_smi_xml(MULTI_GPU).unwrap();
+        assert_eq!(gpus.len(), 2);
+
+        let first = &gpus[0];
+        assert_eq!(first.index, 0);
+        // 名称中的逗号不影响其它字段
+        assert_eq!(first.name, "NVIDIA RTX A6000, Rev. 2");
+        assert_eq!(first.pci_bus_id.as_deref(), Some("00000000:01:00.0"));
+        assert_eq!(first.id, "nvidia:00000000:01:00.0");
+        assert_eq!(first.memory_total_mb, Some(49140));
+        assert_eq!(first.memory_used_mb, Some(20480));
+        assert_eq!(first.memory_free_mb, Some(28356));
+        assert_eq!(first.utilization_percent, Some(87));
+        assert_eq!(first.encoder_utilization_percent, Some(12));
+        assert_eq!(first.decoder_utilization_percent, Some(0));
+        assert_eq!(first.temperature_celsius, Some(71));
+        assert_eq!(first.power_draw_watts, Some(254.31));
+        assert_eq!(first.power_limit_watts, Some(300.0));
+        assert_eq!(first.fan_speed_percent, Some(64));
+        assert_eq!(first.graphics_clock_mhz, Some(1740));
+        assert_eq!(first.memory_clock_mhz, Some(8000));
+        assert_eq!(first.throttle_reasons, vec!["sw_power_cap", "sw_thermal_slowdown"]);
+
+        let pcie = first.pcie.as_ref().unwrap();
+        assert_eq!((pcie.generation, pcie.max_generation), (Some(4), Some(4)));
+        assert_eq!((pcie.width, pcie.max_width), (Some(16), Some(16)));
+
+        let ecc = first.ecc_errors.as_ref().unwrap();
+        assert_eq!((ecc.volatile_corrected, ecc.volatile_uncorrected), (3, 0));
+        assert_eq!((ecc.aggregate_corrected, ecc.aggregate_uncorrected), (17, 1));
+
+        assert_eq!(first.processes.len(), 2);
+        assert_eq!(first.processes[0].pid, 4242);
+        assert_eq!(first.processes[0].name, "python3");
+        assert_eq!(first.processes[0].process_type, "compute");
+        assert_eq!(first.processes[0].used_memory_mb, Some(18944));
+        assert_eq!(first.processes[1].process_type, "graphics");
+
+        let second = &gpus[1];
+        assert_eq!(second.index, 1);
+        assert_eq!(second.name, "NVIDIA GeForce RTX 3060");
+        // 旧驱动的 power_readings / clocks_throttle_reasons / single_bit 格式
+        assert_eq!(second.power_draw_watts, Some(18.5));
+        assert_eq!(second.power_limit_watts, Some(170.0));
+        assert_eq!(second.throttle_reasons, vec!["gpu_idle"]);
+        assert_eq!(second.pcie.as_ref().unwrap().generation, Some(1));
+        let ecc = second.ecc_errors.as_ref().unwrap();
+        assert_eq!((ecc.volatile_corrected, ecc.aggregate_uncorrected), (0, 2));
+        assert!(second.processes.is_empty());
+    }
+
+    #[test]
+    fn keeps_not_available_fields_empty() {
+        let gpus = parse_nvidia_smi_xml(NOT_AVAILABLE).unwrap();
+        assert_eq!(gpus.len(), 1);
+
+        let gpu = &gpus[0];
+        assert_eq!(gpu.name, "NVIDIA A100-SXM4-40GB");
+        assert_eq!(gpu.memory_total_mb, Some(40960));
+        assert_eq!(gpu.memory_used_mb, None);
+        assert_eq!(gpu.utilization_percent, None);
+        assert_eq!(gpu.temperature_celsius, None);
+        assert_eq!(gpu.fan_speed_percent, None);
+        assert_eq!(gpu.power_draw_watts, None);
+        assert_eq!(gpu.encoder_utilization_percent, None);
+        assert!(gpu.ecc_errors.is_none());
+        assert!(gpu.pcie.is_none());
+        assert!(gpu.throttle_reasons.is_empty());
+        assert_eq!(gpu.processes[0].used_memory_mb, None);
+    }
+
+    #[test]
+    fn rejects_unexpected_units() {
+        let xml = "<nvidia_smi_log><gpu><product_name>T4</product_name>\
+                   <fb_memory_usage><total>15 GiB</total></fb_memory_usage>\
+                   <temperature><gpu_temp>95 F</gpu_temp></temperature></gpu></nvidia_smi_log>";
+        let gpu = &parse_nvidia_smi_xml(xml).unwrap()[0];
+        assert_eq!(gpu.memory_total_mb, None);
+        assert_eq!(gpu.temperature_celsius, None);
+    }
+
+    #[test]
+    fn returns_none_for_invalid_output() {
+        assert!(parse_nvidia_smi_xml("").is_none());
+        assert!(parse_nvidia_smi_xml("NVIDIA-SMI has failed because it couldn't communicate with the NVIDIA driver.").is_none());
+        assert!(parse_nvidia_smi_xml("<nvidia_smi_log></nvidia_smi_log>").is_none());
+    }
+}
@@ -1,23 +1,50 @@
 use crate::collectors::get_static_info;
 use crate::config::get_services;
-use crate::models::{Config, RealtimeData, ServiceCard, ServiceStatus};
+use crate::config_reload::ConfigReloader;
+use crate::disk_usage::DiskUsageJobs;
+use crate::models::{Config, ConfigView, ServiceCard, ServiceStatus};
+use crate::realtime_connections::{ConnectionGuard, ConnectionRegistry, ConnectionStats, ConnectionsReport};
+use crate::realtime_protocol::{
+    resolve_topics, ClientMessage, DeltaEncoder, Encoding, RealtimeFrame, RealtimeHistory, ServerMessage, Subscription,
+};
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        ConnectInfo, Query, Request, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
     },
-    http::StatusCode,
-    response::{IntoResponse, Json},
 };
-use futures::{sink::SinkExt, stream::StreamExt};
+use futures::{
+    sink::SinkExt,
+    stream::{SplitSink, StreamExt},
+};
+use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, RwLock};
-use tracing::{error, info, warn};
+use tokio::time::{interval_at, timeout};
+use tracing::{debug, error, info, warn};
+
+/// 降频推送时允许的提前量
+const UPDATE_INTERVAL_SLACK: Duration = Duration::from_millis(100);
+
+/// SSE 保活注释的发送间隔
+const SSE_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<RwLock<Config>>,
-    pub tx: Arc<broadcast::Sender<RealtimeData>>,
+    pub tx: Arc<broadcast::Sender<Arc<RealtimeFrame>>>,
+    pub history: Arc<RealtimeHistory>,
+    pub connections: Arc<ConnectionRegistry>,
+    pub disk_usage: DiskUsageJobs,
+    pub config_reloader: Arc<ConfigReloader>,
 }
 
 pub async fn get_services_handler(
@@ -32,54 +59,412 @@ pub async fn get_static_info_handler() -> Json<crate::models::SystemStaticInfo>
     Json(get_static_info())
 }
 
+#[derive(serde::Deserialize)]
+pub struct RealtimeQuery {
+    /// 推送编码：json（默认）、msgpack 或 cbor
+    format: Option<String>,
+}
+
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
+    Query(query): Query<RealtimeQuery>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state.tx))
+) -> Response {
+    // 查询参数优先，其次为 Sec-WebSocket-Protocol 协商的子协议
+    let requested = match query.format.as_deref() {
+        Some(name) => match Encoding::from_name(name) {
+            Some(encoding) => Some(encoding),
+            None => return (StatusCode::BAD_REQUEST, format!("Unsupported format: {}", name)).into_response(),
+        },
+        None => None,
+    };
+    let config = state.config.read().await;
+    let settings = SocketSettings {
+        default_interval: Duration::from_secs(config.monitoring.collectors.collect_interval_secs),
+        ping_interval: Duration::from_secs(config.server.realtime.ping_interval_secs.max(1)),
+        pong_timeout: Duration::from_secs(config.server.realtime.pong_timeout_secs.max(1)),
+    };
+    drop(config);
+
+    // 子协议只有升级后才能确定，登记时先按查询参数记录
+    let ws = ws.protocols(Encoding::SUBPROTOCOLS);
+    let Some(guard) = state.connections.try_register(
+        "websocket",
+        Some(remote_addr),
+        requested.unwrap_or(Encoding::Json).name(),
+    ) else {
+        warn!("Rejecting WebSocket from {}: connection limit reached", remote_addr);
+        return (StatusCode::SERVICE_UNAVAILABLE, "Too many realtime connections").into_response();
+    };
+
+    ws.on_upgrade(move |socket| {
+        let encoding = requested
+            .or_else(|| socket.protocol()?.to_str().ok().and_then(Encoding::from_name))
+            .unwrap_or(Encoding::Json);
+        handle_socket(socket, state.tx, encoding, settings, guard)
+    })
+    .into_response()
+}
+
+/// WebSocket 连接参数
+#[derive(Debug, Clone, Copy)]
+struct SocketSettings {
+    default_interval: Duration,
+    ping_interval: Duration,
+    pong_timeout: Duration,
 }
 
-async fn handle_socket(socket: WebSocket, tx: Arc<broadcast::Sender<RealtimeData>>) {
-    let (mut sender, mut receiver) = socket.split();
+/// 实时数据推送
+///
+/// 客户端发送 subscribe 之前沿用旧协议，每次推送完整的 RealtimeData（各连接共享同一份编码结果）；
+/// 订阅之后先推送所选主题的快照，之后按订阅的间隔推送 JSON Patch 增量。
+/// 客户端跟不上时跳到最新数据，服务端定期 ping，超时未收到任何消息则断开。
+async fn handle_socket(
+    socket: WebSocket,
+    tx: Arc<broadcast::Sender<Arc<RealtimeFrame>>>,
+    encoding: Encoding,
+    settings: SocketSettings,
+    guard: ConnectionGuard,
+) {
+    let (sender, mut receiver) = socket.split();
+    let mut sender = SocketSender { sink: sender, encoding, timeout: settings.pong_timeout, stats: guard.stats.clone() };
     let mut rx = tx.subscribe();
 
-    // 任务1：从广播通道接收数据并发送到WebSocket
-    let send_task = tokio::spawn(async move {
-        while let Ok(data) = rx.recv().await {
-            let msg = match serde_json::to_string(&data) {
-                Ok(json) => Message::Text(json),
-                Err(e) => {
-                    error!("Failed to serialize realtime data: {}", e);
+    let mut subscription: Option<Subscription> = None;
+    let mut encoder = DeltaEncoder::default();
+    let mut last_sent: Option<Instant> = None;
+    let mut latest: Option<Arc<RealtimeFrame>> = None;
+
+    let mut ping = interval_at(tokio::time::Instant::now() + settings.ping_interval, settings.ping_interval);
+    let mut last_seen = Instant::now();
+
+    loop {
+        tokio::select! {
+            result = rx.recv() => {
+                let frame = match result {
+                    Ok(frame) => frame,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        let (newest, drained) = skip_to_latest(&mut rx);
+                        let skipped = skipped + drained;
+                        guard.stats.record_lag(skipped);
+                        warn!("WebSocket client lagged, skipped {} updates", skipped);
+                        // 旧协议客户端只认识 RealtimeData，不发送通知
+                        if subscription.is_some() && !sender.send_message(&ServerMessage::Lagged { skipped }).await {
+                            break;
+                        }
+                        match newest {
+                            Some(frame) => frame,
+                            None => continue,
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                let bytes = match &subscription {
+                    None => frame.encoded(encoding),
+                    // 按订阅的间隔降频，留出一点余量以免采集抖动导致多跳过一轮
+                    Some(sub) if last_sent.is_some_and(|at| at.elapsed() + UPDATE_INTERVAL_SLACK < sub.interval) => {
+                        latest = Some(frame);
+                        continue;
+                    }
+                    Some(sub) => encoder.encode(sub, &frame, encoding),
+                };
+                latest = Some(frame);
+
+                let Some(bytes) = bytes else {
                     continue;
+                };
+                if !sender.send(&bytes).await {
+                    break;
                 }
-            };
+                last_sent = Some(Instant::now());
+            }
+            message = receiver.next() => {
+                last_seen = Instant::now();
+                // 文本帧总是 JSON，二进制帧使用协商的编码
+                let request = match message {
+                    Some(Ok(Message::Text(text))) => serde_json::from_str::<ClientMessage>(&text).map_err(|e| e.to_string()),
+                    Some(Ok(Message::Binary(bytes))) => encoding.decode::<ClientMessage>(&bytes),
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => {
+                        info!("Client disconnected");
+                        break;
+                    }
+                    // axum 会自动回复 ping，pong 只用于刷新 last_seen
+                    Some(Ok(_)) => continue,
+                };
+
+                let reply = match request {
+                    Ok(ClientMessage::Subscribe { topics, interval_ms }) => {
+                        match Subscription::new(&topics, interval_ms, settings.default_interval) {
+                            Ok(sub) => {
+                                debug!("WebSocket client subscribed to {:?} every {:?}", topics, sub.interval);
+                                subscription = Some(sub);
+                                encoder.reset();
+                                None
+                            }
+                            Err(message) => Some(ServerMessage::Error { message }),
+                        }
+                    }
+                    Ok(ClientMessage::Resync) => {
+                        encoder.reset();
+                        None
+                    }
+                    Err(e) => Some(ServerMessage::Error { message: format!("Invalid message: {}", e) }),
+                };
 
-            if sender.send(msg).await.is_err() {
-                break;
+                // 订阅变化后立即用最近一次数据发送快照，不等下一轮推送
+                let sent = match (reply, &subscription, &latest) {
+                    (Some(reply), _, _) => sender.send_message(&reply).await,
+                    (None, Some(sub), Some(frame)) => match encoder.encode(sub, frame, encoding) {
+                        Some(bytes) => sender.send(&bytes).await,
+                        None => true,
+                    },
+                    (None, _, _) => continue,
+                };
+                if !sent {
+                    break;
+                }
+                last_sent = Some(Instant::now());
             }
-        }
-    });
-
-    // 任务2：处理来自客户端的消息（保持连接活跃）
-    let recv_task = tokio::spawn(async move {
-        while let Some(Ok(msg)) = receiver.next().await {
-            match msg {
-                Message::Close(_) => {
-                    info!("Client disconnected");
+            _ = ping.tick() => {
+                if last_seen.elapsed() > settings.pong_timeout {
+                    info!("Closing unresponsive WebSocket client (no message for {:?})", last_seen.elapsed());
                     break;
                 }
-                Message::Ping(_) => {
-                    // axum 会自动处理 ping/pong
+                if !sender.send_raw(Message::Ping(Vec::new())).await {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// 带超时和统计的 WebSocket 发送端
+struct SocketSender {
+    sink: SplitSink<WebSocket, Message>,
+    encoding: Encoding,
+    timeout: Duration,
+    stats: Arc<ConnectionStats>,
+}
+
+impl SocketSender {
+    /// 发送已编码的数据，连接已断开或发送超时（对端不再读取）时返回 false
+    ///
+    /// axum 的 `Message` 持有自己的缓冲区，共享的编码结果在这里复制一次。
+    async fn send(&mut self, bytes: &[u8]) -> bool {
+        let len = bytes.len();
+        let message = if self.encoding.is_binary() {
+            Message::Binary(bytes.to_vec())
+        } else {
+            // JSON 编码总是合法的 UTF-8
+            match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => Message::Text(text),
+                Err(e) => Message::Text(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+            }
+        };
+        let sent = self.send_raw(message).await;
+        if sent {
+            self.stats.record_sent(len);
+        }
+        sent
+    }
+
+    async fn send_message(&mut self, message: &ServerMessage) -> bool {
+        match self.encoding.encode(message) {
+            Ok(bytes) => self.send(&bytes).await,
+            Err(e) => {
+                error!("Failed to encode WebSocket message: {}", e);
+                true
+            }
+        }
+    }
+
+    async fn send_raw(&mut self, message: Message) -> bool {
+        match timeout(self.timeout, self.sink.send(message)).await {
+            Ok(result) => result.is_ok(),
+            Err(_) => {
+                info!("WebSocket send timed out after {:?}, closing", self.timeout);
+                false
+            }
+        }
+    }
+}
+
+/// 积压后丢弃通道中排队的数据，只保留最新一条；返回最新数据和额外丢弃的条数
+fn skip_to_latest(rx: &mut broadcast::Receiver<Arc<RealtimeFrame>>) -> (Option<Arc<RealtimeFrame>>, u64) {
+    let mut newest = None;
+    let mut dropped = 0;
+    loop {
+        match rx.try_recv() {
+            Ok(frame) => {
+                if newest.replace(frame).is_some() {
+                    dropped += 1;
+                }
+            }
+            Err(broadcast::error::TryRecvError::Lagged(skipped)) => dropped += skipped,
+            Err(_) => break,
+        }
+    }
+    (newest, dropped)
+}
+
+#[derive(serde::Deserialize)]
+pub struct StreamQuery {
+    /// 逗号分隔的主题，默认全部
+    topics: Option<String>,
+}
+
+/// 以 Server-Sent Events 推送实时数据
+///
+/// 每个主题一种事件类型（event: cpu、event: memory ...），数据为该主题的 JSON；
+/// 同一次采样的最后一个事件带有 id，断线重连时按 Last-Event-ID 从历史缓冲区补发。
+/// 客户端跟不上时跳到最新数据，并发送 lagged 事件。
+pub async fn stream_handler(
+    Query(query): Query<StreamQuery>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Response {
+    let requested: Vec<String> = match query.topics.as_deref() {
+        Some(topics) => topics.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from).collect(),
+        None => vec!["*".to_string()],
+    };
+    let topics = match resolve_topics(&requested) {
+        Ok(topics) => topics,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+
+    let Some(guard) = state.connections.try_register("sse", Some(remote_addr), Encoding::Json.name()) else {
+        warn!("Rejecting event stream from {}: connection limit reached", remote_addr);
+        return (StatusCode::SERVICE_UNAVAILABLE, "Too many realtime connections").into_response();
+    };
+
+    // 先订阅再读取历史，两者之间产生的数据不会遗漏
+    let mut rx = state.tx.subscribe();
+    let resume = state.history.resume(last_event_id);
+    if let Some(reason) = resume.reset {
+        debug!("SSE client {} cannot resume from {:?} ({}), resetting", remote_addr, last_event_id, reason);
+    }
+    let history = state.history.clone();
+
+    let stream = async_stream::stream! {
+        // 流结束（客户端断开）时注销连接
+        let stats = guard.stats.clone();
+        let _guard = guard;
+        // 断点已失效时先通知客户端丢弃旧状态，随后发送最近一次的完整数据
+        if let Some(reason) = resume.reset {
+            yield Ok::<_, Infallible>(Event::default().event("reset").data(format!("{{\"reason\":\"{}\"}}", reason)));
+        }
+        let mut last_seq = resume.last_seq;
+        for frame in resume.frames {
+            last_seq = frame.seq;
+            for (event, len) in frame_events(&frame, &topics, &history) {
+                stats.record_sent(len);
+                yield Ok(event);
+            }
+        }
+
+        loop {
+            let frame = match rx.recv().await {
+                Ok(frame) => frame,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    let (newest, drained) = skip_to_latest(&mut rx);
+                    let skipped = skipped + drained;
+                    stats.record_lag(skipped);
+                    warn!("SSE client lagged, skipped {} updates", skipped);
+                    yield Ok(Event::default().event("lagged").data(format!("{{\"skipped\":{}}}", skipped)));
+                    match newest {
+                        Some(frame) => frame,
+                        None => continue,
+                    }
                 }
-                _ => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if frame.seq <= last_seq {
+                continue;
+            }
+            last_seq = frame.seq;
+            for (event, len) in frame_events(&frame, &topics, &history) {
+                stats.record_sent(len);
+                yield Ok(event);
             }
         }
-    });
+    };
+
+    let sse = Sse::new(stream).keep_alive(KeepAlive::new().interval(SSE_KEEP_ALIVE_INTERVAL).text("keep-alive"));
+    // 关闭 nginx 等反向代理的响应缓冲
+    ([("x-accel-buffering", "no")], sse).into_response()
+}
+
+/// 一次采样对应的 SSE 事件及其数据长度
+fn frame_events(frame: &RealtimeFrame, topics: &[&'static str], history: &RealtimeHistory) -> Vec<(Event, usize)> {
+    let mut events: Vec<(Event, usize)> = frame
+        .topic_json()
+        .iter()
+        .filter(|(topic, _)| topics.contains(topic))
+        .map(|(topic, json)| (Event::default().event(*topic).data(json), json.len()))
+        .collect();
+    if let Some((last, len)) = events.pop() {
+        events.push((last.id(history.event_id(frame)), len));
+    }
+    events
+}
+
+/// 实时推送连接的数量和各连接的统计
+pub async fn realtime_connections_handler(State(state): State<AppState>) -> Json<ConnectionsReport> {
+    Json(state.connections.report())
+}
+
+/// 当前生效的配置、被覆盖的键以及是否需要重启
+pub async fn get_config_handler(State(state): State<AppState>) -> Json<ConfigView> {
+    Json(state.config_reloader.view().await)
+}
+
+/// 管理接口鉴权：要求 `Authorization: Bearer <server.admin_token>`
+///
+/// 未配置令牌时管理接口一律返回 403，令牌不符返回 401。
+pub async fn require_admin_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(expected) = state.config.read().await.server.admin_token.clone() else {
+        return (StatusCode::FORBIDDEN, "Admin API is disabled, set server.admin_token to enable it").into_response();
+    };
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match provided {
+        Some(token) if token_matches(token, &expected) => next.run(request).await,
+        _ => (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")], "Invalid or missing admin token")
+            .into_response(),
+    }
+}
+
+// 比较耗时与第一个不同字符的位置无关
+fn token_matches(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    provided.len() == expected.len()
+        && provided.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
 
-    // 等待任一任务完成
-    tokio::select! {
-        _ = send_task => {},
-        _ = recv_task => {},
+/// 按 JSON Merge Patch 修改配置：对象逐键合并，null 删除键，数组整体替换
+///
+/// 校验通过后写回配置文件并立即生效，失败时返回 422 和问题列表，文件保持不变。
+pub async fn update_config_handler(
+    State(state): State<AppState>,
+    Json(patch): Json<serde_json::Value>,
+) -> Response {
+    match state.config_reloader.update(&patch).await {
+        Ok(()) => Json(state.config_reloader.view().await).into_response(),
+        Err(errors) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({ "errors": errors })),
+        ).into_response(),
     }
 }
 
@@ -218,4 +603,30 @@ pub async fn docker_logs_handler(
 #[derive(serde::Deserialize)]
 pub struct LogsParams {
     pub tail: Option<usize>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RealtimeData;
+
+    #[tokio::test]
+    async fn lagged_receiver_skips_to_latest_frame() {
+        let (tx, mut rx) = broadcast::channel(4);
+        for seq in 1..=10 {
+            assert!(tx.send(Arc::new(RealtimeFrame::new(seq, RealtimeData::default()))).is_ok());
+        }
+
+        // 通道只保留最新的 4 条，接收端先收到被覆盖的条数，再丢弃排队的旧数据
+        let Err(broadcast::error::RecvError::Lagged(skipped)) = rx.recv().await else {
+            panic!("receiver should lag");
+        };
+        assert_eq!(skipped, 6);
+        let (newest, drained) = skip_to_latest(&mut rx);
+        assert_eq!(newest.map(|frame| frame.seq), Some(10));
+        assert_eq!(drained, 3);
+
+        let (newest, drained) = skip_to_latest(&mut rx);
+        assert!(newest.is_none());
+        assert_eq!(drained, 0);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::models::{SensorChip, SensorReading, TemperatureSensor};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 被视为 CPU 温度来源的 hwmon 芯片和 thermal zone 类型
+const CPU_SENSOR_SOURCES: &[&str] = &[
+    "coretemp",
+    "k10temp",
+    "zenpower",
+    "cpu_thermal",
+    "cpu-thermal",
+    "soc_thermal",
+    "x86_pkg_temp",
+];
+
+/// RAPL 功耗域的累计能量读数
+#[derive(Debug, Clone)]
+pub struct RaplReading {
+    pub zone: String,
+    pub energy_uj: u64,
+    pub max_energy_range_uj: u64,
+}
+
+/// 温度来源是否属于 CPU
+pub fn is_cpu_sensor_source(source: &str) -> bool {
+    CPU_SENSOR_SOURCES.contains(&source)
+}
+
+/// 读取 `<root>/class/hwmon/hwmon*` 下每个芯片的全部传感器（root 通常为 /sys）
+pub fn read_hwmon_chips(sys_root: &Path) -> Vec<SensorChip> {
+    list_dir_sorted(&sys_root.join("class/hwmon"))
+        .into_iter()
+        .filter_map(|chip_dir| read_hwmon_chip(&chip_dir))
+        .collect()
+}
+
+fn read_hwmon_chip(chip_dir: &Path) -> Option<SensorChip> {
+    // 旧内核的属性文件位于 hwmonN/device/ 下
+    let attr_dir = if chip_dir.join("name").exists() {
+        chip_dir.to_path_buf()
+    } else {
+        chip_dir.join("device")
+    };
+    let name = read_trimmed(&attr_dir.join("name"))?;
+
+    let mut temperatures = Vec::new();
+    let mut fans = Vec::new();
+    let mut voltages = Vec::new();
+    let mut currents = Vec::new();
+
+    for input in list_dir_sorted(&attr_dir) {
+        let file = file_name(&input);
+        let Some(prefix) = file.strip_suffix("_input") else {
+            continue;
+        };
+        let kind = prefix.trim_end_matches(|c: char| c.is_ascii_digit());
+        let index: u32 = prefix[kind.len()..].parse().unwrap_or(0);
+
+        // temp 为毫摄氏度，in 为毫伏，curr 为毫安，fan 为 RPM
+        let (readings, scale) = match kind {
+            "temp" => (&mut temperatures, 1000.0),
+            "fan" => (&mut fans, 1.0),
+            "in" => (&mut voltages, 1000.0),
+            "curr" => (&mut currents, 1000.0),
+            _ => continue,
+        };
+        let read_value = |suffix: &str| {
+            read_trimmed(&attr_dir.join(format!("{}_{}", prefix, suffix)))
+                .and_then(|v| v.parse::<i64>().ok())
+                .map(|v| v as f32 / scale)
+        };
+        // 传感器未接入或读取失败（EIO、ENODATA）时跳过
+        let Some(value) = read_value("input") else {
+            continue;
+        };
+        let alarm = ["alarm", "crit_alarm", "max_alarm", "min_alarm"]
+            .iter()
+            .any(|suffix| read_trimmed(&attr_dir.join(format!("{}_{}", prefix, suffix))).as_deref() == Some("1"));
+
+        readings.push((
+            index,
+            SensorReading {
+                label: read_trimmed(&attr_dir.join(format!("{}_label", prefix)))
+                    .unwrap_or_else(|| prefix.to_string()),
+                value,
+                min: read_value("min"),
+                max: read_value("max"),
+                crit: read_value("crit"),
+                alarm,
+            },
+        ));
+    }
+
+    Some(SensorChip {
+        name,
+        hwmon: file_name(chip_dir),
+        // 同名芯片（多块 nvme、多路 coretemp）靠所属设备区分
+        device: chip_dir
+            .join("device")
+            .canonicalize()
+            .ok()
+            .map(|path| file_name(&path)),
+        temperatures: sorted_by_index(temperatures),
+        fans: sorted_by_index(fans),
+        voltages: sorted_by_index(voltages),
+        currents: sorted_by_index(currents),
+    })
+}
+
+// 目录按字典序排列（temp10 在 temp2 之前），按通道编号重新排序
+fn sorted_by_index(mut readings: Vec<(u32, SensorReading)>) -> Vec<SensorReading> {
+    readings.sort_by_key(|(index, _)| *index);
+    readings.into_iter().map(|(_, reading)| reading).collect()
+}
+
+/// 从 hwmon 芯片中挑出 CPU 温度
+pub fn cpu_temperatures(chips: &[SensorChip]) -> Vec<TemperatureSensor> {
+    chips
+        .iter()
+        .filter(|chip| is_cpu_sensor_source(&chip.name))
+        .flat_map(|chip| {
+            chip.temperatures.iter().map(|reading| TemperatureSensor {
+                source: chip.name.clone(),
+                label: reading.label.clone(),
+                celsius: reading.value,
+            })
+        })
+        .collect()
+}
+
+/// 读取 `<root>/class/thermal/thermal_zone*`
+pub fn read_thermal_zones(sys_root: &Path) -> Vec<TemperatureSensor> {
+    list_dir_sorted(&sys_root.join("class/thermal"))
+        .into_iter()
+        .filter(|dir| file_name(dir).starts_with("thermal_zone"))
+        .filter_map(|dir| {
+            let millidegrees = read_trimmed(&dir.join("temp"))?.parse::<i64>().ok()?;
+            Some(TemperatureSensor {
+                source: read_trimmed(&dir.join("type")).unwrap_or_else(|| "thermal".to_string()),
+                label: file_name(&dir),
+                celsius: millidegrees as f32 / 1000.0,
+            })
+        })
+        .collect()
+}
+
+/// 读取 `<root>/class/powercap/intel-rapl:N` 的 package 级能量计数器
+///
+/// 子域（intel-rapl:0:0 的 core、uncore、dram）已包含在 package 中，不重复统计。
+pub fn read_rapl_packages(sys_root: &Path) -> Vec<RaplReading> {
+    list_dir_sorted(&sys_root.join("class/powercap"))
+        .into_iter()
+        .filter(|dir| {
+            let name = file_name(dir);
+            name.strip_prefix("intel-rapl:").is_some_and(|rest| !rest.contains(':'))
+        })
+        .filter_map(|dir| {
+            Some(RaplReading {
+                zone: read_trimmed(&dir.join("name")).unwrap_or_else(|| file_name(&dir)),
+                energy_uj: read_trimmed(&dir.join("energy_uj"))?.parse().ok()?,
+                max_energy_range_uj: read_trimmed(&dir.join("max_energy_range_uj"))
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(u64::MAX),
+            })
+        })
+        .collect()
+}
+
+fn list_dir_sorted(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).collect(),
+        Err(_) => Vec::new(),
+    };
+    entries.sort();
+    entries
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sys_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hwmon-sysfs")
+    }
+
+    #[test]
+    fn reads_hwmon_chips() {
+        let chips = read_hwmon_chips(&sys_root());
+        // hwmon2 没有 name，跳过
+        let names: Vec<&str> = chips.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["coretemp", "nct6775"]);
+
+        let coretemp = &chips[0];
+        assert_eq!(coretemp.hwmon, "hwmon0");
+        assert_eq!(coretemp.device, None);
+        // 读不出数值的 temp3 跳过，temp10 排在 temp2 之后
+        let labels: Vec<&str> = coretemp.temperatures.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, ["Package id 0", "Core 0", "Core 8"]);
+        let package = &coretemp.temperatures[0];
+        assert_eq!((package.value, package.max, package.crit), (52.0, Some(80.0), Some(100.0)));
+        assert!(!package.alarm);
+    }
+
+    #[test]
+    fn reads_legacy_device_attributes() {
+        let chips = read_hwmon_chips(&sys_root());
+        let nct = &chips[1];
+
+        assert_eq!(nct.fans.len(), 1);
+        assert_eq!((nct.fans[0].label.as_str(), nct.fans[0].value, nct.fans[0].min), ("fan1", 1200.0, Some(300.0)));
+        assert_eq!(nct.voltages[0].value, 1.016);
+        assert_eq!(nct.currents[0].value, 2.5);
+        assert!(nct.temperatures[0].alarm);
+    }
+
+    #[test]
+    fn picks_cpu_temperatures() {
+        let temperatures = cpu_temperatures(&read_hwmon_chips(&sys_root()));
+
+        assert_eq!(temperatures.len(), 3);
+        assert!(temperatures.iter().all(|t| t.source == "coretemp"));
+        assert_eq!(temperatures[1].celsius, 48.0);
+        assert!(is_cpu_sensor_source("k10temp"));
+        assert!(!is_cpu_sensor_source("nvme"));
+    }
+
+    #[test]
+    fn reads_thermal_zones() {
+        let zones = read_thermal_zones(&sys_root());
+
+        assert_eq!(zones.len(), 2);
+        assert_eq!((zones[0].source.as_str(), zones[0].label.as_str()), ("x86_pkg_temp", "thermal_zone0"));
+        assert_eq!(zones[0].celsius, 53.0);
+        assert_eq!(zones[1].celsius, -1.0);
+    }
+
+    #[test]
+    fn reads_rapl_packages_only() {
+        let packages = read_rapl_packages(&sys_root());
+
+        // intel-rapl:0:0 是子域，intel-rapl:2 只有 name，没有能量计数器
+        let zones: Vec<&str> = packages.iter().map(|p| p.zone.as_str()).collect();
+        assert_eq!(zones, ["package-0", "package-1"]);
+        assert_eq!(packages[0].energy_uj, 123456789);
+        assert_eq!(packages[0].max_energy_range_uj, 262143328850);
+        assert_eq!(packages[1].max_energy_range_uj, u64::MAX);
+    }
+}
@@ -1,67 +1,189 @@
 mod collectors;
+mod collector_tasks;
 mod collector_utils;
 mod collector_config;
+mod cli;
 mod config;
+mod config_edit;
+mod config_env;
+mod config_reload;
 mod handlers;
+mod realtime_protocol;
+mod realtime_connections;
 mod health;
 mod models;
 mod docker_parser;
+mod connection_parser;
+mod net_stats;
+mod disk_stats;
+mod smart_parser;
+mod storage_pool_parser;
+mod hwmon;
+mod cpu_stats;
+mod pressure;
+mod mem_stats;
+mod gpu;
 mod file_handlers;
+mod disk_usage;
 
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{delete, get, post},
     Router,
 };
+use clap::Parser;
+use cli::{Cli, Command};
 use handlers::AppState;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, watch, RwLock};
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
-use tracing::info;
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() {
+    let cli = Cli::parse();
+
+    match cli.command() {
+        Command::Serve => serve(cli).await,
+        Command::CheckConfig => {
+            let valid = config::check_config(&cli.config, &cli.overrides()).await;
+            std::process::exit(if valid { 0 } else { 1 });
+        }
+        Command::PrintDefaultConfig => match toml::to_string_pretty(&config::default_config()) {
+            Ok(text) => print!("{}", text),
+            Err(e) => {
+                eprintln!("Failed to serialize default configuration: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Command::Snapshot { sample_ms, pretty } => {
+            // stdout 只输出 JSON，日志写到 stderr
+            init_logging(cli.log_level.as_deref(), true);
+            let Some(config) = config::load_config(&cli.config, cli.allow_default_config, &cli.overrides()).await else {
+                std::process::exit(1);
+            };
+            let data = collectors::SystemCollector::snapshot(
+                config.monitoring,
+                std::time::Duration::from_millis(sample_ms),
+            ).await;
+            let json = if pretty { serde_json::to_string_pretty(&data) } else { serde_json::to_string(&data) };
+            match json {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Failed to serialize snapshot: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+}
+
+/// 初始化日志；`--log-level` 优先于 RUST_LOG
+fn init_logging(log_level: Option<&str>, to_stderr: bool) {
+    let filter = match log_level {
+        // 单独的级别同时作用于本程序和 tower_http
+        Some(level) if !level.contains(['=', ',']) => {
+            tracing_subscriber::EnvFilter::try_new(format!("index_rs={0},tower_http={0}", level))
+        }
+        Some(directives) => tracing_subscriber::EnvFilter::try_new(directives),
+        None => Ok(tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "index_rs=info,tower_http=info".into())),
+    };
+    let filter = filter.unwrap_or_else(|e| {
+        eprintln!("Invalid --log-level: {}", e);
+        std::process::exit(2);
+    });
+
+    let registry = tracing_subscriber::registry().with(filter);
+    if to_stderr {
+        registry.with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr)).init();
+    } else {
+        registry.with(tracing_subscriber::fmt::layer()).init();
+    }
+}
+
+async fn serve(cli: Cli) {
     // 初始化日志
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "index_rs=info,tower_http=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    init_logging(cli.log_level.as_deref(), false);
 
     info!("Starting index-rs server...");
 
-    // 加载配置
-    let config = config::load_config().await;
+    // 加载配置；文件缺失或无效时默认退出，--allow-default-config 时使用内置默认配置
+    let overrides = cli.overrides();
+    let Some(config) = config::load_config(&cli.config, cli.allow_default_config, &overrides).await else {
+        std::process::exit(1);
+    };
     let server_host = config.server.host.clone();
     let server_port = config.server.port;
+    let static_dir = std::path::PathBuf::from(config.server.static_dir.as_deref().unwrap_or("static"));
+    // 显式配置的目录已在校验时检查过，这里只提示默认目录缺失
+    if !std::path::Path::new(config.files.storage_path()).is_dir() {
+        warn!("File storage path {} not found, file manager requests will fail", config.files.storage_path());
+    }
     
     // 创建广播通道用于实时数据
-    let (tx, _rx) = broadcast::channel::<models::RealtimeData>(100);
+    let (tx, _rx) = broadcast::channel::<Arc<realtime_protocol::RealtimeFrame>>(100);
     let tx = Arc::new(tx);
+    // 最近的推送记录，供 SSE 断线续传
+    let history = Arc::new(realtime_protocol::RealtimeHistory::new(
+        config.monitoring.performance.history_size
+    ));
+
+    // 监控配置变化通过 watch 通道通知采集器
+    let (monitoring_tx, monitoring_rx) = watch::channel(config.monitoring.clone());
 
     // 启动系统信息采集器
     let collector = collectors::SystemCollector::new(
         tx.as_ref().clone(),
-        config.monitoring.clone()
+        history.clone(),
+        monitoring_rx
     );
     tokio::spawn(collector.start());
 
+    // 实时推送连接登记（连接数上限和统计）
+    let connections = Arc::new(realtime_connections::ConnectionRegistry::new(
+        config.server.realtime.max_connections
+    ));
+
+    let disk_usage = disk_usage::DiskUsageJobs::new(std::time::Duration::from_secs(
+        config.monitoring.performance.disk_usage_cache_ttl_secs
+    ));
+    let config = Arc::new(RwLock::new(config));
+
+    // 配置文件热加载，以及管理接口的写回
+    let config_reloader = config_reload::ConfigReloader::new(
+        cli.config.clone(),
+        overrides,
+        config.clone(),
+        monitoring_tx,
+    ).await;
+    config_reloader.spawn_watcher();
+
     // 创建应用状态
     let app_state = AppState {
-        config: Arc::new(RwLock::new(config)),
+        disk_usage,
+        config,
         tx: tx.clone(),
+        history,
+        connections,
+        config_reloader,
     };
 
-    // 检查静态目录
-    let static_dir = std::path::Path::new("static");
-    if !static_dir.exists() {
-        panic!("Static directory not found!");
+    // 检查静态目录，缺失时只提供 API
+    match static_dir.canonicalize() {
+        Ok(path) if path.is_dir() => info!("Static directory found at: {:?}", path),
+        _ => warn!("Static directory {:?} not found, serving the API only", static_dir),
     }
-    info!("Static directory found at: {:?}", static_dir.canonicalize());
+
+    // 管理接口和会遍历整个文件系统的目录扫描需要令牌
+    let require_admin = middleware::from_fn_with_state(app_state.clone(), handlers::require_admin_token);
+    let admin_routes = Router::new()
+        .route("/connections", get(handlers::realtime_connections_handler))
+        .route("/config", get(handlers::get_config_handler).patch(handlers::update_config_handler))
+        .route_layer(require_admin.clone());
 
     // 构建应用路由
     let app = Router::new()
@@ -69,6 +191,8 @@ async fn main() {
         .route("/api/services", get(handlers::get_services_handler))
         .route("/api/system/static", get(handlers::get_static_info_handler))
         .route("/ws/realtime", get(handlers::websocket_handler))
+        .route("/api/stream", get(handlers::stream_handler))
+        .nest("/api/admin", admin_routes)
         // Docker API
         .route("/api/docker/action", post(handlers::docker_action_handler))
         .route("/api/docker/logs/:container_id", get(handlers::docker_logs_handler))
@@ -78,20 +202,34 @@ async fn main() {
         .route("/api/files/download/*file_path", get(file_handlers::download_file_handler))
         .route("/api/files/mkdir", post(file_handlers::create_directory_handler))
         .route("/api/files/delete", post(file_handlers::delete_file_handler))
+        // 目录大小分析 API
+        .route("/api/disk-usage/scan", post(disk_usage::start_scan_handler).route_layer(require_admin.clone()))
+        .route("/api/disk-usage/jobs", get(disk_usage::list_jobs_handler))
+        .route(
+            "/api/disk-usage/jobs/:job_id",
+            get(disk_usage::get_job_handler).merge(delete(disk_usage::delete_job_handler).route_layer(require_admin)),
+        )
+        .route("/api/disk-usage/jobs/:job_id/tree", get(disk_usage::get_tree_handler))
         .with_state(app_state)
         // 静态文件服务
-        .fallback_service(ServeDir::new("static"))
+        .fallback_service(ServeDir::new(&static_dir))
         // CORS 支持
         .layer(CorsLayer::permissive());
 
-    // 绑定地址
-    let addr: SocketAddr = format!("{}:{}", server_host, server_port)
-        .parse()
-        .expect("Invalid server address");
-    info!("Server listening on http://{}", addr);
+    // 绑定地址（host 可以是主机名）
+    let listener = match tokio::net::TcpListener::bind((server_host.as_str(), server_port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to bind {}:{}: {}", server_host, server_port, e);
+            std::process::exit(1);
+        }
+    };
+    match listener.local_addr() {
+        Ok(addr) => info!("Server listening on http://{}", addr),
+        Err(_) => info!("Server listening on http://{}:{}", server_host, server_port),
+    }
 
     // 启动服务器
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     info!("Server bound successfully, starting to serve...");
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+use crate::models::ZfsArcInfo;
+use std::collections::HashMap;
+
+/// 解析 `/proc/meminfo`，返回 字段名 -> 数值
+///
+/// 大部分字段单位为 kB，HugePages_* 为页数。
+pub fn parse_meminfo(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// 解析 `/proc/vmstat`（"pswpin 123" 每行一个计数器）
+pub fn parse_vmstat(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// 解析 `/proc/spl/kstat/zfs/arcstats`
+///
+/// 前两行为 kstat 头部，之后每行为 "name type data"。
+pub fn parse_arcstats(content: &str) -> Option<ZfsArcInfo> {
+    let stats: HashMap<&str, u64> = content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            Some((*parts.first()?, parts.get(2)?.parse().ok()?))
+        })
+        .collect();
+
+    Some(ZfsArcInfo {
+        size_kb: stats.get("size")? / 1024,
+        target_kb: stats.get("c").map(|v| v / 1024).unwrap_or(0),
+        max_kb: stats.get("c_max").map(|v| v / 1024).unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_meminfo() {
+        let meminfo = parse_meminfo(
+            "MemTotal:       32780544 kB\nMemAvailable:   20123456 kB\nSwapCached:            0 kB\nHugePages_Total:       4\nBroken line\n",
+        );
+
+        assert_eq!(meminfo["MemTotal"], 32780544);
+        assert_eq!(meminfo["MemAvailable"], 20123456);
+        assert_eq!(meminfo["SwapCached"], 0);
+        assert_eq!(meminfo["HugePages_Total"], 4);
+        assert_eq!(meminfo.len(), 4);
+    }
+
+    #[test]
+    fn parses_vmstat() {
+        let vmstat = parse_vmstat("pswpin 120\npswpout 3456\noom_kill 2\nbogus\n");
+
+        assert_eq!(vmstat["pswpin"], 120);
+        assert_eq!(vmstat["pswpout"], 3456);
+        assert_eq!(vmstat["oom_kill"], 2);
+        assert_eq!(vmstat.len(), 3);
+    }
+
+    #[test]
+    fn parses_arcstats() {
+        let arc = parse_arcstats(
+            "13 1 0x01 123 33456 8925212571 1223451212312\nname                            type data\nhits                            4    1234567\nc                               4    8589934592\nc_max                           4    17179869184\nsize                            4    4294967296\n",
+        )
+        .unwrap();
+
+        assert_eq!(arc.size_kb, 4194304);
+        assert_eq!(arc.target_kb, 8388608);
+        assert_eq!(arc.max_kb, 16777216);
+    }
+
+    #[test]
+    fn arcstats_without_size_is_ignored() {
+        assert!(parse_arcstats("13 1 0x01\nname type data\nhits 4 1\n").is_none());
+    }
+}
@@ -26,30 +26,82 @@ pub struct SystemStaticInfo {
     pub total_memory_gb: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RealtimeData {
     pub timestamp: i64,
     pub cpu: CpuInfo,
     pub memory: MemoryInfo,
     pub disks: Vec<DiskInfo>,
+    pub disk_io: Vec<DiskIoInfo>,
+    pub smart: Vec<SmartInfo>,
+    pub storage_pools: Vec<StoragePoolInfo>,
+    pub sensors: Vec<SensorChip>,
     pub network: NetworkInfo,
     pub load_average: LoadAverage,
+    pub pressure: Option<PressureInfo>,  // 内核未启用 PSI 时为空
     pub uptime_secs: u64,
     pub gpu: Option<Vec<GpuInfo>>,
     pub ports: Vec<PortInfo>,
+    pub connections: ConnectionsInfo,
     pub processes: Vec<ProcessInfo>,
     pub docker_containers: Vec<DockerContainer>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CpuInfo {
     pub total_usage: f32,
     pub core_usage: Vec<f32>,
     pub temperature_celsius: Option<f32>,  // CPU 温度
     pub power_watts: Option<f32>,          // CPU 功耗
+    pub temperatures: Vec<TemperatureSensor>,  // 各个 CPU 温度传感器
+    pub core_frequency_mhz: Vec<f32>,      // 各核心当前频率
+    pub governor: Option<String>,          // 调频策略（虚拟机通常没有）
+    pub time_breakdown: Option<CpuTimeBreakdown>,  // 各状态时间占比（首次采样为空）
+    pub context_switches_per_sec: Option<f64>,
+    pub interrupts_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuTimeBreakdown {
+    pub user: f32,      // 百分比
+    pub nice: f32,
+    pub system: f32,
+    pub idle: f32,
+    pub iowait: f32,
+    pub irq: f32,
+    pub softirq: f32,
+    pub steal: f32,     // 虚拟机被宿主机占用的时间
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemperatureSensor {
+    pub source: String,   // hwmon 芯片名或 thermal zone 类型
+    pub label: String,
+    pub celsius: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorChip {
+    pub name: String,                    // 芯片名：coretemp、k10temp、nvme、nct6775 ...
+    pub hwmon: String,                   // hwmonN
+    pub device: Option<String>,          // 所属设备：nvme0、0000:00:18.3 ...
+    pub temperatures: Vec<SensorReading>,  // 摄氏度
+    pub fans: Vec<SensorReading>,          // RPM
+    pub voltages: Vec<SensorReading>,      // 伏特
+    pub currents: Vec<SensorReading>,      // 安培
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub label: String,
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub crit: Option<f32>,     // 临界阈值
+    pub alarm: bool,           // 芯片报告的告警状态
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryInfo {
     pub total_kb: u64,
     pub used_kb: u64,
@@ -59,11 +111,37 @@ pub struct MemoryInfo {
     pub swap_used_kb: u64,
     pub swap_free_kb: u64,
     pub swap_used_percent: f32,
+    // 以下来自 /proc/meminfo，单位 kB
+    pub available_kb: u64,
+    pub buffers_kb: u64,
+    pub cached_kb: u64,
+    pub shared_kb: u64,
+    pub slab_kb: u64,
+    pub slab_reclaimable_kb: u64,
+    pub dirty_kb: u64,
+    pub writeback_kb: u64,
+    pub hugepages_total: u64,            // 大页数量
+    pub hugepages_free: u64,
+    pub hugepage_size_kb: u64,
+    pub zfs_arc: Option<ZfsArcInfo>,     // 未加载 ZFS 时为空
+    // 以下来自 /proc/vmstat
+    pub swap_in_pages_per_sec: Option<f64>,
+    pub swap_out_pages_per_sec: Option<f64>,
+    pub oom_kills: Option<u64>,          // 开机以来 OOM killer 触发次数
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZfsArcInfo {
+    pub size_kb: u64,     // 当前 ARC 大小
+    pub target_kb: u64,   // 目标大小（c）
+    pub max_kb: u64,      // 上限（c_max）
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskInfo {
     pub name: String,
+    pub device: Option<String>,  // 对应 disk_io 中的块设备名
+    pub smart_health: Option<String>,  // 所在物理盘的 SMART 状态
     pub mount_point: String,
     pub file_system: String,
     pub total_gb: f64,
@@ -73,6 +151,84 @@ pub struct DiskInfo {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskIoInfo {
+    pub device: String,
+    pub mount_points: Vec<String>,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub avg_queue_depth: f64,
+    pub utilization_percent: f32,
+    pub read_await_ms: f64,
+    pub write_await_ms: f64,
+    pub await_ms: f64,
+    pub ios_in_progress: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartInfo {
+    pub device: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub protocol: String,        // ATA, NVMe, SCSI
+    pub health_status: String,   // PASSED, FAILED, UNKNOWN
+    pub temperature_celsius: Option<f32>,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub offline_uncorrectable: Option<u64>,
+    pub nvme_percentage_used: Option<u8>,   // NVMe 磨损程度
+    pub nvme_available_spare: Option<u8>,
+    pub nvme_media_errors: Option<u64>,
+    pub alerts: Vec<SmartAlert>,
+    pub standby: bool,  // 磁盘休眠中，其余字段是休眠前最后一次读取的结果
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartAlert {
+    pub metric: String,
+    pub severity: String,        // warning 或 critical
+    pub value: f64,
+    pub threshold: Option<f64>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoragePoolInfo {
+    pub name: String,
+    pub pool_type: String,           // mdraid, zfs, btrfs
+    pub level: Option<String>,       // raid1, mirror, raidz2 等
+    pub health: String,
+    pub degraded: bool,
+    pub size_bytes: Option<u64>,
+    pub allocated_bytes: Option<u64>,
+    pub free_bytes: Option<u64>,
+    pub members: Vec<PoolMember>,
+    pub operation: Option<PoolOperation>,  // 重建、同步或巡检
+    pub errors: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolMember {
+    pub name: String,
+    pub state: String,
+    pub read_errors: u64,
+    pub write_errors: u64,
+    pub checksum_errors: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolOperation {
+    pub kind: String,                // resync, recovery, resilver, scrub 等
+    pub in_progress: bool,
+    pub progress_percent: Option<f32>,
+    pub eta: Option<String>,
+    pub speed: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NetworkInfo {
     pub interfaces: Vec<NetworkInterface>,
     pub rx_speed_kbps: f64,
@@ -86,52 +242,213 @@ pub struct NetworkInterface {
     pub name: String,
     pub ipv4: Vec<String>,
     pub ipv6: Vec<String>,
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub operstate: String,         // up, down, unknown 等
+    pub is_up: bool,
+    pub speed_mbps: Option<u32>,   // 链路速率，虚拟网卡为空
+    pub is_virtual: bool,
+    pub rx_speed_kbps: f64,
+    pub tx_speed_kbps: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub total_rx_bytes: u64,
+    pub total_tx_bytes: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LoadAverage {
     pub one: f64,
     pub five: f64,
     pub fifteen: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureInfo {
+    pub cpu: Option<PressureResource>,
+    pub memory: Option<PressureResource>,
+    pub io: Option<PressureResource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureResource {
+    pub some: Option<PressureLine>,  // 至少一个任务停顿
+    pub full: Option<PressureLine>,  // 所有非空闲任务同时停顿
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureLine {
+    pub avg10: f32,                   // 过去 10 秒停顿时间百分比
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total_us: u64,                // 累计停顿微秒数
+    pub stall_percent: Option<f64>,   // 本次采样间隔内的停顿时间百分比
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuInfo {
+    pub id: String,        // 跨厂商唯一的标识，如 nvidia:00000000:01:00.0
+    pub vendor: String,    // nvidia / amd / intel
     pub name: String,
-    pub index: u32,
-    pub memory_total_mb: u32,
-    pub memory_used_mb: u32,
-    pub memory_free_mb: u32,
-    pub utilization_percent: u8,
-    pub temperature_celsius: u8,
+    pub index: u32,        // nvidia-smi 序号或 DRM cardN 编号，不同厂商之间可能重复
+    pub pci_bus_id: Option<String>,
+    // 以下字段驱动不支持或报告 N/A 时为空，而不是 0
+    pub memory_total_mb: Option<u32>,
+    pub memory_used_mb: Option<u32>,
+    pub memory_free_mb: Option<u32>,
+    pub utilization_percent: Option<u8>,
+    pub temperature_celsius: Option<u8>,
     pub power_draw_watts: Option<f32>,
     pub power_limit_watts: Option<f32>,
     pub fan_speed_percent: Option<u8>,
     pub graphics_clock_mhz: Option<u32>,
     pub memory_clock_mhz: Option<u32>,
+    pub encoder_utilization_percent: Option<u8>,
+    pub decoder_utilization_percent: Option<u8>,
+    pub throttle_reasons: Vec<String>,      // 当前生效的降频原因：sw_power_cap、hw_thermal_slowdown ...
+    pub pcie: Option<PcieLinkInfo>,
+    pub ecc_errors: Option<EccErrorCounts>,  // 未开启 ECC 时为空
+    pub processes: Vec<GpuProcess>,  // 占用该 GPU 的进程
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PcieLinkInfo {
+    pub generation: Option<u8>,       // 当前链路代数（空闲时会降速）
+    pub max_generation: Option<u8>,
+    pub width: Option<u8>,            // 当前链路宽度 x1/x8/x16
+    pub max_width: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EccErrorCounts {
+    pub volatile_corrected: u64,      // 自上次驱动加载以来
+    pub volatile_uncorrected: u64,
+    pub aggregate_corrected: u64,     // 显卡生命周期累计
+    pub aggregate_uncorrected: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuProcess {
+    pub pid: u32,
+    pub name: String,
+    pub used_memory_mb: Option<u32>,
+    pub process_type: String,            // compute / graphics
+    pub container_id: Option<String>,    // 进程所在的 Docker 容器
+    pub container_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub services: ServicesConfig,
     #[serde(default)]
     pub monitoring: crate::collector_config::GlobalConfig,
+    #[serde(default)]
+    pub files: FilesConfig,
+    #[serde(default)]
+    pub disk_usage: DiskUsageConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
+    /// 前端静态文件目录，未设置时使用工作目录下的 static
+    #[serde(default)]
+    pub static_dir: Option<String>,
+    /// 管理接口（/api/admin/*）的访问令牌，请求需带 `Authorization: Bearer <令牌>`；未设置时管理接口不可用
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    #[serde(default)]
+    pub realtime: RealtimeConfig,
+}
+
+/// 实时推送（WebSocket / SSE）连接配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealtimeConfig {
+    /// 同时保持的最大连接数
+    #[serde(default = "default_max_realtime_connections")]
+    pub max_connections: usize,
+    /// WebSocket ping 间隔（秒）
+    #[serde(default = "default_ping_interval")]
+    pub ping_interval_secs: u64,
+    /// 超过该时间未收到客户端任何消息（含 pong）即断开（秒）
+    #[serde(default = "default_pong_timeout")]
+    pub pong_timeout_secs: u64,
+}
+
+impl Default for RealtimeConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: default_max_realtime_connections(),
+            ping_interval_secs: default_ping_interval(),
+            pong_timeout_secs: default_pong_timeout(),
+        }
+    }
+}
+
+/// 管理接口返回的配置
+#[derive(Debug, Serialize)]
+pub struct ConfigView {
+    /// 当前生效的配置（文件、环境变量和命令行覆盖合并后）
+    pub config: Config,
+    /// 被环境变量或命令行参数覆盖的键，写入文件的修改对它们不生效
+    pub overrides: Vec<ConfigOverrideInfo>,
+    /// 文件中的 [server] 已修改，重启后生效
+    pub restart_required: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigOverrideInfo {
+    /// 键路径，如 server.port
+    pub key: String,
+    /// 环境变量名或命令行参数
+    pub source: String,
+}
+
+/// 文件管理配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilesConfig {
+    /// 文件管理的根目录，未设置时使用 `/home/xianyu/uploads`；旧的 FILE_STORAGE_PATH 环境变量仍然有效
+    #[serde(default)]
+    pub storage_path: Option<String>,
+}
+
+impl FilesConfig {
+    /// 实际使用的根目录
+    pub fn storage_path(&self) -> &str {
+        self.storage_path.as_deref().unwrap_or(DEFAULT_STORAGE_PATH)
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// 目录大小分析配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskUsageConfig {
+    /// 除挂载点之外允许扫描的目录（含其子目录）；挂载点本身总是允许
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    /// 同时进行的扫描任务上限
+    #[serde(default = "default_max_concurrent_scans")]
+    pub max_concurrent_scans: usize,
+}
+
+impl Default for DiskUsageConfig {
+    fn default() -> Self {
+        Self { allowed_paths: Vec::new(), max_concurrent_scans: default_max_concurrent_scans() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServicesConfig {
     pub ip: String,  // 所有服务共享的IP地址
     pub items: Vec<ServiceCardConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceCardConfig {
     pub name: String,
     pub port: u16,
@@ -153,6 +470,43 @@ pub struct PortInfo {
     pub address: String,   // 监听地址
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub protocol: String,                // tcp
+    pub state: String,                   // ESTAB, TIME-WAIT 等
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: String,
+    pub remote_port: u16,
+    pub remote_hostname: Option<String>, // 反向 DNS 解析结果
+    pub program: String,
+    pub pid: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteHostSummary {
+    pub remote_address: String,
+    pub remote_hostname: Option<String>,
+    pub connection_count: usize,
+    pub local_ports: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalPortSummary {
+    pub local_port: u16,
+    pub program: String,
+    pub connection_count: usize,
+    pub remote_addresses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionsInfo {
+    pub total: usize,
+    pub connections: Vec<ConnectionInfo>,
+    pub by_remote_host: Vec<RemoteHostSummary>,
+    pub by_local_port: Vec<LocalPortSummary>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
@@ -164,6 +518,8 @@ pub struct ProcessInfo {
     pub user: Option<String>,
     pub command: String,
     pub start_time: Option<i64>,
+    pub gpu_memory_mb: Option<u32>,  // 在所有 GPU 上占用的显存
+    pub gpu_ids: Vec<String>,        // 使用中的 GPU（GpuInfo::id）
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -181,6 +537,11 @@ pub struct DockerContainer {
     pub memory_percent: f32,
     pub network_rx_mb: f64,
     pub network_tx_mb: f64,
+    pub network_rx_kbps: f64,
+    pub network_tx_kbps: f64,
+    pub block_read_kbps: f64,   // 来自容器 cgroup 的块设备读写速率
+    pub block_write_kbps: f64,
+    pub gpu_memory_mb: Option<u32>,  // 容器内进程占用的显存合计
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -252,6 +613,38 @@ pub struct DirectoryListing {
     pub files: Vec<FileInfo>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageNode {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,     // 按实际占用块计算
+    pub file_count: u64,
+    pub dir_count: u64,
+    pub child_count: usize,  // 直接子目录总数，children 可能被截断
+    pub children: Vec<DiskUsageNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageProgress {
+    pub files_scanned: u64,
+    pub dirs_scanned: u64,
+    pub bytes_scanned: u64,
+    pub errors: u64,
+    pub current_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageJob {
+    pub id: String,
+    pub path: String,
+    pub status: String,      // running, completed, failed, cancelled
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub progress: DiskUsageProgress,
+    pub error: Option<String>,
+    pub cached: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UploadResponse {
     pub success: bool,
@@ -261,4 +654,22 @@ pub struct UploadResponse {
 
 fn default_protocol() -> String {
     "http".to_string()
+}
+
+const DEFAULT_STORAGE_PATH: &str = "/home/xianyu/uploads";
+
+fn default_max_concurrent_scans() -> usize {
+    2
+}
+
+fn default_max_realtime_connections() -> usize {
+    64
+}
+
+fn default_ping_interval() -> u64 {
+    15
+}
+
+fn default_pong_timeout() -> u64 {
+    45
 }
\ No newline at end of file
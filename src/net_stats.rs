@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 网卡名 -> (IPv4 列表, IPv6 列表)
+pub type InterfaceAddresses = HashMap<String, (Vec<String>, Vec<String>)>;
+
+/// 单个网卡的累计计数器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// 从 sysfs 读取的网卡快照
+#[derive(Debug, Clone)]
+pub struct InterfaceSnapshot {
+    pub name: String,
+    pub counters: InterfaceCounters,
+    pub mtu: Option<u32>,
+    pub mac_address: Option<String>,
+    pub operstate: String,
+    pub is_up: bool,
+    pub speed_mbps: Option<u32>,
+    pub is_virtual: bool,
+}
+
+/// 读取 `<root>/class/net` 下所有网卡（root 通常为 /sys）
+pub fn read_sysfs_interfaces(sys_root: &Path) -> Vec<InterfaceSnapshot> {
+    let net_dir = sys_root.join("class/net");
+    let entries = match fs::read_dir(&net_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut interfaces: Vec<InterfaceSnapshot> = entries
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let dir = entry.path();
+            let stat = |file: &str| read_u64(&dir.join("statistics").join(file)).unwrap_or(0);
+
+            let counters = InterfaceCounters {
+                rx_bytes: stat("rx_bytes"),
+                tx_bytes: stat("tx_bytes"),
+                rx_packets: stat("rx_packets"),
+                tx_packets: stat("tx_packets"),
+                rx_errors: stat("rx_errors"),
+                tx_errors: stat("tx_errors"),
+                rx_dropped: stat("rx_dropped"),
+                tx_dropped: stat("tx_dropped"),
+            };
+
+            // 未连接或虚拟网卡读取 speed 会失败或返回 -1
+            let speed_mbps = read_trimmed(&dir.join("speed"))
+                .and_then(|s| s.parse::<i64>().ok())
+                .filter(|s| *s > 0)
+                .map(|s| s as u32);
+
+            let mac_address = read_trimmed(&dir.join("address"))
+                .filter(|mac| !mac.is_empty() && mac != "00:00:00:00:00:00");
+
+            let operstate = read_trimmed(&dir.join("operstate")).unwrap_or_else(|| "unknown".to_string());
+            // lo 等网卡的 operstate 始终为 unknown，此时以 IFF_UP 标志为准
+            let admin_up = read_trimmed(&dir.join("flags"))
+                .and_then(|f| u32::from_str_radix(f.trim_start_matches("0x"), 16).ok())
+                .is_some_and(|f| f & 0x1 != 0);
+            let is_up = operstate == "up" || (operstate == "unknown" && admin_up);
+
+            InterfaceSnapshot {
+                is_virtual: is_virtual_interface(&name, &dir),
+                mtu: read_u64(&dir.join("mtu")).map(|m| m as u32),
+                operstate,
+                is_up,
+                mac_address,
+                speed_mbps,
+                counters,
+                name,
+            }
+        })
+        .collect();
+
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// 判断是否为虚拟网卡（lo、docker0、veth*、网桥等）
+pub fn is_virtual_interface(name: &str, sysfs_dir: &Path) -> bool {
+    // 物理网卡的链接指向 /sys/devices/pci...，虚拟网卡指向 /sys/devices/virtual/net
+    if let Ok(target) = fs::read_link(sysfs_dir) {
+        return target.to_string_lossy().contains("/virtual/");
+    }
+
+    name == "lo"
+        || ["docker", "veth", "br-", "virbr", "vnet", "tun", "tap", "cni", "flannel", "cali"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+/// 解析 `ip -j addr show` 的输出
+pub fn parse_ip_addr_json(output: &str) -> InterfaceAddresses {
+    let mut addresses = HashMap::new();
+
+    let links: Vec<serde_json::Value> = match serde_json::from_str(output) {
+        Ok(links) => links,
+        Err(_) => return addresses,
+    };
+
+    for link in links {
+        let Some(name) = link.get("ifname").and_then(|v| v.as_str()) else {
+            continue;
+        };
+
+        let mut ipv4 = Vec::new();
+        let mut ipv6 = Vec::new();
+        for addr in link.get("addr_info").and_then(|v| v.as_array()).into_iter().flatten() {
+            let (Some(family), Some(local)) = (
+                addr.get("family").and_then(|v| v.as_str()),
+                addr.get("local").and_then(|v| v.as_str()),
+            ) else {
+                continue;
+            };
+            let formatted = match addr.get("prefixlen").and_then(|v| v.as_u64()) {
+                Some(prefix) => format!("{}/{}", local, prefix),
+                None => local.to_string(),
+            };
+            match family {
+                "inet" => ipv4.push(formatted),
+                "inet6" => ipv6.push(formatted),
+                _ => {}
+            }
+        }
+
+        addresses.insert(name.to_string(), (ipv4, ipv6));
+    }
+
+    addresses
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_trimmed(path).and_then(|s| s.parse::<u64>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sys_root() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/net-sysfs")
+    }
+
+    #[test]
+    fn reads_interfaces_from_sysfs() {
+        let interfaces = read_sysfs_interfaces(&sys_root());
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["docker0", "eth0", "lo"]);
+
+        let eth0 = &interfaces[1];
+        assert!(eth0.is_up && !eth0.is_virtual);
+        assert_eq!(eth0.speed_mbps, Some(1000));
+        assert_eq!(eth0.mtu, Some(1500));
+        assert_eq!(eth0.mac_address.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(eth0.counters.rx_bytes, 123456789);
+        assert_eq!(eth0.counters.rx_errors, 2);
+        assert_eq!(eth0.counters.tx_dropped, 1);
+    }
+
+    #[test]
+    fn loopback_is_up_by_flags() {
+        let interfaces = read_sysfs_interfaces(&sys_root());
+
+        let lo = &interfaces[2];
+        assert_eq!(lo.operstate, "unknown");
+        assert!(lo.is_up && lo.is_virtual);
+        assert_eq!(lo.mac_address, None);
+        assert_eq!(lo.speed_mbps, None);
+        // 缺少的计数器按 0 处理
+        assert_eq!(lo.counters.rx_packets, 0);
+
+        let docker0 = &interfaces[0];
+        assert!(!docker0.is_up && docker0.is_virtual);
+        assert_eq!(docker0.speed_mbps, None);
+    }
+
+    #[test]
+    fn missing_sysfs_yields_nothing() {
+        assert!(read_sysfs_interfaces(Path::new("/nonexistent")).is_empty());
+    }
+
+    #[test]
+    fn parses_ip_addr_json() {
+        let output = r#"[
+            {"ifindex": 1, "ifname": "lo", "addr_info": [
+                {"family": "inet", "local": "127.0.0.1", "prefixlen": 8},
+                {"family": "inet6", "local": "::1", "prefixlen": 128}
+            ]},
+            {"ifindex": 2, "ifname": "eth0", "addr_info": [
+                {"family": "inet", "local": "192.168.1.10", "prefixlen": 24},
+                {"family": "inet", "local": "192.168.1.11"},
+                {"family": "inet6", "local": "fe80::5054:ff:fe12:3456", "prefixlen": 64}
+            ]},
+            {"ifindex": 3, "ifname": "wg0"}
+        ]"#;
+
+        let addresses = parse_ip_addr_json(output);
+        assert_eq!(addresses["eth0"].0, ["192.168.1.10/24", "192.168.1.11"]);
+        assert_eq!(addresses["eth0"].1, ["fe80::5054:ff:fe12:3456/64"]);
+        assert_eq!(addresses["lo"].1, ["::1/128"]);
+        assert_eq!(addresses["wg0"], (Vec::new(), Vec::new()));
+        assert!(parse_ip_addr_json("not json").is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::collector_utils::RateTracker;
+use crate::models::{PressureInfo, PressureLine, PressureResource};
+use std::path::Path;
+use std::time::Instant;
+
+/// 解析 `/proc/pressure/<resource>` 的内容
+///
+/// ```text
+/// some avg10=0.00 avg60=0.12 avg300=0.05 total=1234567
+/// full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+/// ```
+pub fn parse_pressure(content: &str) -> Vec<(String, [f32; 3], u64)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let kind = parts.next()?.to_string();
+            let mut averages = [0.0; 3];
+            let mut total = 0;
+            for part in parts {
+                let (key, value) = part.split_once('=')?;
+                match key {
+                    "avg10" => averages[0] = value.parse().ok()?,
+                    "avg60" => averages[1] = value.parse().ok()?,
+                    "avg300" => averages[2] = value.parse().ok()?,
+                    "total" => total = value.parse().ok()?,
+                    _ => {}
+                }
+            }
+            Some((kind, averages, total))
+        })
+        .collect()
+}
+
+/// 读取 cpu、memory、io 三类压力，内核未启用 PSI 时返回 None
+pub async fn collect_pressure(proc_root: &Path, rates: &mut RateTracker) -> Option<PressureInfo> {
+    let mut info = PressureInfo {
+        cpu: None,
+        memory: None,
+        io: None,
+    };
+    let now = Instant::now();
+
+    for resource in ["cpu", "memory", "io"] {
+        let Ok(content) = tokio::fs::read_to_string(proc_root.join("pressure").join(resource)).await else {
+            continue;
+        };
+
+        let mut pressure = PressureResource { some: None, full: None };
+        for (kind, [avg10, avg60, avg300], total_us) in parse_pressure(&content) {
+            // total 为累计停顿微秒数，每秒增量 / 10^4 即为停顿时间占比
+            let stall_percent = rates
+                .rate(&format!("psi:{}:{}", resource, kind), total_us, now)
+                .map(|us_per_sec| us_per_sec / 10_000.0);
+            let line = PressureLine { avg10, avg60, avg300, total_us, stall_percent };
+            match kind.as_str() {
+                "some" => pressure.some = Some(line),
+                "full" => pressure.full = Some(line),
+                _ => {}
+            }
+        }
+
+        match resource {
+            "cpu" => info.cpu = Some(pressure),
+            "memory" => info.memory = Some(pressure),
+            _ => info.io = Some(pressure),
+        }
+    }
+
+    if info.cpu.is_none() && info.memory.is_none() && info.io.is_none() {
+        return None;
+    }
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full_lines() {
+        let lines = parse_pressure(
+            "some avg10=0.00 avg60=0.12 avg300=0.05 total=1234567\nfull avg10=2.50 avg60=1.00 avg300=0.25 total=42\n",
+        );
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], ("some".to_string(), [0.0, 0.12, 0.05], 1234567));
+        assert_eq!(lines[1], ("full".to_string(), [2.5, 1.0, 0.25], 42));
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let lines = parse_pressure("some avg10=abc total=1\nsome avg10\nfull avg10=1.00 avg60=0.00 avg300=0.00 total=7\n");
+
+        assert_eq!(lines, [("full".to_string(), [1.0, 0.0, 0.0], 7)]);
+    }
+
+    #[tokio::test]
+    async fn collects_available_resources() {
+        let proc_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pressure-proc");
+        let mut rates = RateTracker::new();
+
+        let info = collect_pressure(&proc_root, &mut rates).await.unwrap();
+        // 没有 io 文件；cpu 只有 some 行
+        assert!(info.io.is_none());
+        let cpu = info.cpu.unwrap();
+        assert!(cpu.full.is_none());
+        let some = cpu.some.unwrap();
+        assert_eq!((some.avg10, some.total_us), (1.5, 123456));
+        // 首次采样没有速率
+        assert_eq!(some.stall_percent, None);
+        assert_eq!(info.memory.unwrap().full.unwrap().total_us, 890);
+    }
+
+    #[tokio::test]
+    async fn returns_none_without_psi() {
+        assert!(collect_pressure(Path::new("/nonexistent"), &mut RateTracker::new()).await.is_none());
+    }
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 实时推送连接（WebSocket 和 SSE）的登记表，负责连接数上限和统计
+pub struct ConnectionRegistry {
+    max_connections: usize,
+    next_id: AtomicU64,
+    rejected_total: AtomicU64,
+    connections: Mutex<BTreeMap<u64, Arc<ConnectionStats>>>,
+}
+
+/// 单个连接的计数器
+pub struct ConnectionStats {
+    id: u64,
+    transport: &'static str,
+    remote_addr: Option<SocketAddr>,
+    encoding: &'static str,
+    connected_at: DateTime<Utc>,
+    messages_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    lag_events: AtomicU64,
+    dropped_updates: AtomicU64,
+}
+
+impl ConnectionStats {
+    pub fn record_sent(&self, bytes: usize) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// 客户端跟不上推送，跳过了 `skipped` 条数据
+    pub fn record_lag(&self, skipped: u64) {
+        self.lag_events.fetch_add(1, Ordering::Relaxed);
+        self.dropped_updates.fetch_add(skipped, Ordering::Relaxed);
+    }
+
+    fn report(&self) -> ConnectionReport {
+        ConnectionReport {
+            id: self.id,
+            transport: self.transport,
+            remote_addr: self.remote_addr.map(|addr| addr.to_string()),
+            encoding: self.encoding,
+            connected_at: self.connected_at,
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            lag_events: self.lag_events.load(Ordering::Relaxed),
+            dropped_updates: self.dropped_updates.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 登记成功的连接，drop 时自动注销
+pub struct ConnectionGuard {
+    registry: Arc<ConnectionRegistry>,
+    pub stats: Arc<ConnectionStats>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.registry.lock().remove(&self.stats.id);
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectionsReport {
+    pub active: usize,
+    pub max_connections: usize,
+    pub rejected_total: u64,
+    pub connections: Vec<ConnectionReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectionReport {
+    pub id: u64,
+    pub transport: &'static str,
+    pub remote_addr: Option<String>,
+    pub encoding: &'static str,
+    pub connected_at: DateTime<Utc>,
+    pub messages_sent: u64,
+    pub bytes_sent: u64,
+    /// 发生积压的次数
+    pub lag_events: u64,
+    /// 因积压被跳过的数据条数
+    pub dropped_updates: u64,
+}
+
+impl ConnectionRegistry {
+    pub fn new(max_connections: usize) -> Self {
+        Self {
+            max_connections,
+            next_id: AtomicU64::new(1),
+            rejected_total: AtomicU64::new(0),
+            connections: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// 登记新连接，已达上限时返回 None
+    pub fn try_register(
+        self: &Arc<Self>,
+        transport: &'static str,
+        remote_addr: Option<SocketAddr>,
+        encoding: &'static str,
+    ) -> Option<ConnectionGuard> {
+        let mut connections = self.lock();
+        if connections.len() >= self.max_connections {
+            self.rejected_total.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        let stats = Arc::new(ConnectionStats {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            transport,
+            remote_addr,
+            encoding,
+            connected_at: Utc::now(),
+            messages_sent: AtomicU64::new(0),
+            bytes_sent: AtomicU64::new(0),
+            lag_events: AtomicU64::new(0),
+            dropped_updates: AtomicU64::new(0),
+        });
+        connections.insert(stats.id, stats.clone());
+
+        Some(ConnectionGuard { registry: self.clone(), stats })
+    }
+
+    pub fn report(&self) -> ConnectionsReport {
+        let connections = self.lock();
+        ConnectionsReport {
+            active: connections.len(),
+            max_connections: self.max_connections,
+            rejected_total: self.rejected_total.load(Ordering::Relaxed),
+            connections: connections.values().map(|stats| stats.report()).collect(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, Arc<ConnectionStats>>> {
+        self.connections.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_connections_over_the_limit() {
+        let registry = Arc::new(ConnectionRegistry::new(2));
+        let first = registry.try_register("websocket", None, "json").unwrap();
+        let _second = registry.try_register("sse", None, "json").unwrap();
+        assert!(registry.try_register("websocket", None, "msgpack").is_none());
+
+        let report = registry.report();
+        assert_eq!(report.active, 2);
+        assert_eq!(report.rejected_total, 1);
+
+        // 断开一个后空出名额
+        drop(first);
+        assert_eq!(registry.report().active, 1);
+        let third = registry.try_register("websocket", None, "msgpack").unwrap();
+        assert_eq!(third.stats.id, 3);
+        assert_eq!(registry.report().rejected_total, 1);
+    }
+
+    #[test]
+    fn reports_connection_stats() {
+        let registry = Arc::new(ConnectionRegistry::new(4));
+        let addr: SocketAddr = "192.168.1.5:51234".parse().unwrap();
+        let guard = registry.try_register("websocket", Some(addr), "cbor").unwrap();
+        guard.stats.record_sent(100);
+        guard.stats.record_sent(50);
+        guard.stats.record_lag(3);
+        guard.stats.record_lag(2);
+
+        let report = registry.report();
+        let connection = &report.connections[0];
+        assert_eq!(connection.transport, "websocket");
+        assert_eq!(connection.remote_addr.as_deref(), Some("192.168.1.5:51234"));
+        assert_eq!(connection.encoding, "cbor");
+        assert_eq!((connection.messages_sent, connection.bytes_sent), (2, 150));
+        assert_eq!((connection.lag_events, connection.dropped_updates), (2, 5));
+
+        drop(guard);
+        assert!(registry.report().connections.is_empty());
+    }
+}
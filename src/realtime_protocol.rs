@@ -0,0 +1,612 @@
+use crate::models::RealtimeData;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::error;
+
+/// 主题与 `RealtimeData` 字段的对应关系，timestamp 总是包含在内
+const TOPICS: &[(&str, &[&str])] = &[
+    ("cpu", &["cpu", "load_average", "uptime_secs"]),
+    ("memory", &["memory"]),
+    ("disks", &["disks", "disk_io", "smart", "storage_pools"]),
+    ("network", &["network"]),
+    ("pressure", &["pressure"]),
+    ("sensors", &["sensors"]),
+    ("gpu", &["gpu"]),
+    ("ports", &["ports"]),
+    ("connections", &["connections"]),
+    ("processes", &["processes"]),
+    ("docker", &["docker_containers"]),
+];
+
+/// 订阅所有主题
+const ALL_TOPICS: &str = "*";
+
+/// 推送间隔下限，避免客户端要求过高的频率
+const MIN_UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 将主题列表（可含 `*`）解析为去重后的已知主题
+pub fn resolve_topics(topics: &[String]) -> Result<Vec<&'static str>, String> {
+    let mut resolved = Vec::new();
+    for topic in topics {
+        let names: Vec<&'static str> = if topic == ALL_TOPICS {
+            TOPICS.iter().map(|(name, _)| *name).collect()
+        } else {
+            let (name, _) = TOPICS
+                .iter()
+                .find(|(name, _)| name == topic)
+                .ok_or_else(|| format!("Unknown topic: {}", topic))?;
+            vec![*name]
+        };
+        for name in names {
+            if !resolved.contains(&name) {
+                resolved.push(name);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+fn topic_fields(topic: &str) -> &'static [&'static str] {
+    TOPICS
+        .iter()
+        .find(|(name, _)| *name == topic)
+        .map(|(_, fields)| *fields)
+        .unwrap_or_default()
+}
+
+/// 客户端发往服务端的消息
+///
+/// ```json
+/// {"type": "subscribe", "topics": ["cpu", "memory"], "interval_ms": 2000}
+/// {"type": "resync"}
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// 设置订阅（替换之前的订阅），随后发送完整快照
+    Subscribe {
+        topics: Vec<String>,
+        #[serde(default)]
+        interval_ms: Option<u64>,
+    },
+    /// 要求重新发送完整快照（客户端状态出错时使用）
+    Resync,
+}
+
+/// 服务端发往已订阅客户端的消息
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// 已订阅主题的完整数据
+    Snapshot { data: Value },
+    /// 相对上一条消息的 JSON Patch（RFC 6902）
+    Delta { patch: json_patch::Patch },
+    /// 客户端处理过慢，服务端跳过了 `skipped` 条数据，下一条消息基于最新数据
+    Lagged { skipped: u64 },
+    /// 订阅请求无效
+    Error { message: String },
+}
+
+/// 单个客户端的订阅
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    fields: BTreeSet<&'static str>,
+    pub interval: Duration,
+}
+
+impl Subscription {
+    /// 解析主题列表，未知主题返回错误
+    pub fn new(topics: &[String], interval_ms: Option<u64>, default_interval: Duration) -> Result<Self, String> {
+        let mut fields = BTreeSet::from(["timestamp"]);
+        for topic in resolve_topics(topics)? {
+            fields.extend(topic_fields(topic).iter().copied());
+        }
+
+        Ok(Self {
+            fields,
+            interval: interval_ms
+                .map(Duration::from_millis)
+                .unwrap_or(default_interval)
+                .max(MIN_UPDATE_INTERVAL),
+        })
+    }
+
+    /// 只保留订阅的字段
+    fn select(&self, data: &Value) -> Value {
+        let Value::Object(object) = data else {
+            return Value::Null;
+        };
+        let selected: Map<String, Value> = object
+            .iter()
+            .filter(|(key, _)| self.fields.contains(key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        Value::Object(selected)
+    }
+}
+
+/// 记录上一次发送的帧序号和内容，生成快照或增量
+#[derive(Debug, Default)]
+pub struct DeltaEncoder {
+    last: Option<(u64, Arc<Value>)>,
+}
+
+impl DeltaEncoder {
+    /// 生成 `frame` 对应的消息：首次调用或 reset 之后为快照，其余为相对上一条的增量
+    ///
+    /// 编码结果缓存在帧上，订阅相同主题、从同一帧出发的客户端共享同一份字节。
+    pub fn encode(&mut self, subscription: &Subscription, frame: &RealtimeFrame, encoding: Encoding) -> Option<Arc<[u8]>> {
+        let current = frame.selected(subscription)?;
+        let key = (subscription.fields.clone(), self.last.as_ref().map(|(seq, _)| *seq), encoding);
+        let bytes = frame.update(key, || {
+            let message = match &self.last {
+                Some((_, last)) => ServerMessage::Delta { patch: json_patch::diff(last, &current) },
+                None => ServerMessage::Snapshot { data: current.as_ref().clone() },
+            };
+            encoding
+                .encode(&message)
+                .inspect_err(|e| error!("Failed to encode WebSocket message: {}", e))
+                .ok()
+                .map(Arc::from)
+        });
+        self.last = Some((frame.seq, current));
+        bytes
+    }
+
+    /// 下一条消息改为完整快照
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// 推送消息的编码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl Encoding {
+    /// WebSocket 子协议名称，同时可用于 `?format=` 查询参数
+    pub const SUBPROTOCOLS: [&'static str; 3] = ["json", "msgpack", "cbor"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "msgpack" | "messagepack" => Some(Self::MessagePack),
+            "cbor" => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+            Self::Cbor => "cbor",
+        }
+    }
+
+    pub fn is_binary(self) -> bool {
+        self != Self::Json
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            // 以 map 形式编码结构体，客户端解码后得到带字段名的对象
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(value, &mut buffer).map_err(|e| e.to_string())?;
+                Ok(buffer)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// 订阅客户端消息的缓存键：订阅的字段、增量基准帧的序号（快照为 None）和编码
+type UpdateKey = (BTreeSet<&'static str>, Option<u64>, Encoding);
+
+/// 一次推送的数据及其编码结果
+///
+/// 通过广播通道以 `Arc` 共享给所有客户端，每种编码和 JSON 值在首次使用时生成一次，
+/// 不再由每个连接各自序列化。
+pub struct RealtimeFrame {
+    /// 递增序号，SSE 事件 ID 的后半部分
+    pub seq: u64,
+    pub data: RealtimeData,
+    value: OnceLock<Option<Value>>,
+    topics: OnceLock<Vec<(&'static str, String)>>,
+    json: OnceLock<Option<Arc<[u8]>>>,
+    msgpack: OnceLock<Option<Arc<[u8]>>>,
+    cbor: OnceLock<Option<Arc<[u8]>>>,
+    /// 按订阅字段筛选后的数据
+    selections: Mutex<HashMap<BTreeSet<&'static str>, Arc<Value>>>,
+    /// 订阅客户端的快照和增量
+    updates: Mutex<HashMap<UpdateKey, Option<Arc<[u8]>>>>,
+}
+
+impl RealtimeFrame {
+    pub fn new(seq: u64, data: RealtimeData) -> Self {
+        Self {
+            seq,
+            data,
+            value: OnceLock::new(),
+            topics: OnceLock::new(),
+            json: OnceLock::new(),
+            msgpack: OnceLock::new(),
+            cbor: OnceLock::new(),
+            selections: Mutex::new(HashMap::new()),
+            updates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 完整数据的 JSON 值，供按主题筛选和计算增量
+    pub fn value(&self) -> Option<&Value> {
+        self.value
+            .get_or_init(|| {
+                serde_json::to_value(&self.data)
+                    .inspect_err(|e| error!("Failed to convert realtime data: {}", e))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// 按主题拆分的 JSON 文本，每项包含 timestamp 和该主题的字段
+    pub fn topic_json(&self) -> &[(&'static str, String)] {
+        self.topics.get_or_init(|| {
+            let Some(Value::Object(object)) = self.value() else {
+                return Vec::new();
+            };
+            TOPICS
+                .iter()
+                .filter_map(|(topic, fields)| {
+                    let selected: Map<String, Value> = std::iter::once("timestamp")
+                        .chain(fields.iter().copied())
+                        .filter_map(|field| Some((field.to_string(), object.get(field)?.clone())))
+                        .collect();
+                    serde_json::to_string(&selected)
+                        .inspect_err(|e| error!("Failed to encode topic {}: {}", topic, e))
+                        .ok()
+                        .map(|json| (*topic, json))
+                })
+                .collect()
+        })
+    }
+
+    /// 订阅字段对应的数据，相同订阅的客户端共享
+    fn selected(&self, subscription: &Subscription) -> Option<Arc<Value>> {
+        let value = self.value()?;
+        let mut selections = self.selections.lock().unwrap_or_else(|e| e.into_inner());
+        let selected = selections
+            .entry(subscription.fields.clone())
+            .or_insert_with(|| Arc::new(subscription.select(value)));
+        Some(selected.clone())
+    }
+
+    /// 查找或生成订阅客户端的消息，编码在锁外进行
+    fn update(&self, key: UpdateKey, encode: impl FnOnce() -> Option<Arc<[u8]>>) -> Option<Arc<[u8]>> {
+        if let Some(bytes) = self.updates.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return bytes.clone();
+        }
+        let bytes = encode();
+        self.updates
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_insert(bytes)
+            .clone()
+    }
+
+    /// 完整数据按指定格式编码后的字节
+    pub fn encoded(&self, encoding: Encoding) -> Option<Arc<[u8]>> {
+        let cell = match encoding {
+            Encoding::Json => &self.json,
+            Encoding::MessagePack => &self.msgpack,
+            Encoding::Cbor => &self.cbor,
+        };
+        cell.get_or_init(|| {
+            encoding
+                .encode(&self.data)
+                .inspect_err(|e| error!("Failed to encode realtime data as {:?}: {}", encoding, e))
+                .ok()
+                .map(Arc::from)
+        })
+        .clone()
+    }
+}
+
+/// 最近若干次推送，供 SSE 客户端按 Last-Event-ID 续传
+///
+/// 事件 ID 形如 `<boot_id>-<seq>`，boot_id 每个进程不同，
+/// 重启后旧连接带来的序号不会被误认为本进程的序号。
+pub struct RealtimeHistory {
+    inner: Mutex<HistoryInner>,
+    capacity: usize,
+    boot_id: String,
+}
+
+/// 按 Last-Event-ID 续传的结果
+pub struct Resume {
+    /// 无法从断点继续时的原因（"restarted" 或 "expired"），客户端应丢弃旧状态
+    pub reset: Option<&'static str>,
+    /// 需要补发的帧
+    pub frames: Vec<Arc<RealtimeFrame>>,
+    /// 客户端已经收到的最大序号，广播中不大于它的帧跳过
+    pub last_seq: u64,
+}
+
+struct HistoryInner {
+    frames: VecDeque<Arc<RealtimeFrame>>,
+    next_seq: u64,
+}
+
+impl RealtimeHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(HistoryInner { frames: VecDeque::new(), next_seq: 1 }),
+            capacity: capacity.max(1),
+            boot_id: boot_id(),
+        }
+    }
+
+    /// 帧对应的 SSE 事件 ID
+    pub fn event_id(&self, frame: &RealtimeFrame) -> String {
+        format!("{}-{}", self.boot_id, frame.seq)
+    }
+
+    /// 分配序号并记录，返回用于广播的帧
+    pub fn record(&self, data: RealtimeData) -> Arc<RealtimeFrame> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = Arc::new(RealtimeFrame::new(inner.next_seq, data));
+        inner.next_seq += 1;
+        inner.frames.push_back(frame.clone());
+        while inner.frames.len() > self.capacity {
+            inner.frames.pop_front();
+        }
+        frame
+    }
+
+    /// 根据客户端的 Last-Event-ID 决定补发哪些帧
+    ///
+    /// 没有 ID 时只发送最近一帧；ID 属于本进程且之后的帧都还在缓冲区时补发这些帧；
+    /// 其它进程的 ID、无法解析或超前的序号，以及已被丢弃的断点，都要求客户端重置并从最近一帧开始。
+    pub fn resume(&self, last_event_id: Option<&str>) -> Resume {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let latest = || Vec::from_iter(inner.frames.back().cloned());
+        let Some(last_event_id) = last_event_id else {
+            return Resume { reset: None, frames: latest(), last_seq: 0 };
+        };
+
+        let seq = last_event_id
+            .rsplit_once('-')
+            .filter(|(boot_id, _)| *boot_id == self.boot_id)
+            .and_then(|(_, seq)| seq.parse::<u64>().ok())
+            .filter(|seq| *seq < inner.next_seq);
+        let Some(seq) = seq else {
+            return Resume { reset: Some("restarted"), frames: latest(), last_seq: 0 };
+        };
+        let oldest = inner.frames.front().map_or(inner.next_seq, |frame| frame.seq);
+        if seq + 1 < oldest {
+            return Resume { reset: Some("expired"), frames: latest(), last_seq: 0 };
+        }
+        let frames = inner.frames.iter().filter(|frame| frame.seq > seq).cloned().collect();
+        Resume { reset: None, frames, last_seq: seq }
+    }
+}
+
+// 进程启动时间（纳秒，十六进制），足以区分同一端口上先后运行的进程
+fn boot_id() -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos());
+    format!("{:x}", nanos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data(timestamp: i64, cpu_usage: f32) -> RealtimeData {
+        let mut data = RealtimeData { timestamp, ..Default::default() };
+        data.cpu.total_usage = cpu_usage;
+        data.memory.total_kb = 1024;
+        data
+    }
+
+    fn subscription(topics: &[&str]) -> Subscription {
+        let topics: Vec<String> = topics.iter().map(|t| t.to_string()).collect();
+        Subscription::new(&topics, None, Duration::from_secs(2)).unwrap()
+    }
+
+    fn decode(encoding: Encoding, bytes: &[u8]) -> Value {
+        encoding.decode::<Value>(bytes).unwrap()
+    }
+
+    fn seqs(frames: &[Arc<RealtimeFrame>]) -> Vec<u64> {
+        frames.iter().map(|frame| frame.seq).collect()
+    }
+
+    #[test]
+    fn resolves_topics() {
+        assert_eq!(resolve_topics(&["cpu".into(), "memory".into(), "cpu".into()]).unwrap(), ["cpu", "memory"]);
+        assert_eq!(resolve_topics(&["*".into()]).unwrap().len(), TOPICS.len());
+        assert!(resolve_topics(&["storage".into()]).is_err());
+    }
+
+    #[test]
+    fn subscription_interval_has_lower_bound() {
+        let sub = Subscription::new(&["cpu".into()], Some(10), Duration::from_secs(2)).unwrap();
+        assert_eq!(sub.interval, MIN_UPDATE_INTERVAL);
+        assert_eq!(subscription(&["cpu"]).interval, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn sends_snapshot_then_json_patch_delta() {
+        let sub = subscription(&["cpu"]);
+        let history = RealtimeHistory::new(4);
+        let mut encoder = DeltaEncoder::default();
+
+        let first = history.record(data(1, 10.0));
+        let snapshot = decode(Encoding::Json, &encoder.encode(&sub, &first, Encoding::Json).unwrap());
+        assert_eq!(snapshot["type"], "snapshot");
+        // 只包含订阅主题的字段和 timestamp
+        let mut state = snapshot["data"].clone();
+        assert_eq!(state["timestamp"], 1);
+        assert!(state.get("memory").is_none());
+        assert!(state.get("load_average").is_some());
+
+        let second = history.record(data(2, 55.0));
+        let delta = decode(Encoding::Json, &encoder.encode(&sub, &second, Encoding::Json).unwrap());
+        assert_eq!(delta["type"], "delta");
+        let patch: json_patch::Patch = serde_json::from_value(delta["patch"].clone()).unwrap();
+        json_patch::patch(&mut state, &patch).unwrap();
+        assert_eq!(state, sub.select(second.value().unwrap()));
+
+        encoder.reset();
+        let resync = decode(Encoding::Json, &encoder.encode(&sub, &second, Encoding::Json).unwrap());
+        assert_eq!(resync["type"], "snapshot");
+    }
+
+    #[test]
+    fn clients_with_same_subscription_share_encoding() {
+        let history = RealtimeHistory::new(4);
+        let first = history.record(data(1, 10.0));
+        let second = history.record(data(2, 20.0));
+
+        let mut a = DeltaEncoder::default();
+        let mut b = DeltaEncoder::default();
+        let mut c = DeltaEncoder::default();
+        let snapshot_a = a.encode(&subscription(&["cpu"]), &first, Encoding::Json).unwrap();
+        let snapshot_b = b.encode(&subscription(&["cpu"]), &first, Encoding::Json).unwrap();
+        assert!(Arc::ptr_eq(&snapshot_a, &snapshot_b));
+
+        let delta_a = a.encode(&subscription(&["cpu"]), &second, Encoding::Json).unwrap();
+        let delta_b = b.encode(&subscription(&["cpu"]), &second, Encoding::Json).unwrap();
+        assert!(Arc::ptr_eq(&delta_a, &delta_b));
+
+        // 从快照开始的客户端与增量客户端不共享
+        let snapshot_c = c.encode(&subscription(&["cpu"]), &second, Encoding::Json).unwrap();
+        assert!(!Arc::ptr_eq(&delta_a, &snapshot_c));
+        // 不同编码或主题各自缓存
+        let msgpack = DeltaEncoder::default().encode(&subscription(&["cpu"]), &second, Encoding::MessagePack).unwrap();
+        let memory = DeltaEncoder::default().encode(&subscription(&["memory"]), &second, Encoding::Json).unwrap();
+        assert_ne!(msgpack, snapshot_c);
+        assert_ne!(memory, snapshot_c);
+    }
+
+    #[test]
+    fn binary_encodings_round_trip() {
+        let frame = RealtimeFrame::new(1, data(7, 12.5));
+        let expected = serde_json::to_value(&frame.data).unwrap();
+
+        for encoding in [Encoding::Json, Encoding::MessagePack, Encoding::Cbor] {
+            let bytes = frame.encoded(encoding).unwrap();
+            assert_eq!(decode(encoding, &bytes), expected, "{:?}", encoding);
+            // 同一帧只编码一次
+            assert!(Arc::ptr_eq(&bytes, &frame.encoded(encoding).unwrap()));
+        }
+        assert!(Encoding::MessagePack.is_binary() && Encoding::Cbor.is_binary() && !Encoding::Json.is_binary());
+        assert_eq!(Encoding::from_name("messagepack"), Some(Encoding::MessagePack));
+        assert_eq!(Encoding::from_name("xml"), None);
+    }
+
+    #[test]
+    fn decodes_client_messages() {
+        let message: ClientMessage = Encoding::Json
+            .decode(br#"{"type": "subscribe", "topics": ["cpu"], "interval_ms": 1000}"#)
+            .unwrap();
+        assert!(matches!(message, ClientMessage::Subscribe { interval_ms: Some(1000), .. }));
+
+        let bytes = Encoding::Cbor.encode(&json!({"type": "resync"})).unwrap();
+        assert!(matches!(Encoding::Cbor.decode::<ClientMessage>(&bytes).unwrap(), ClientMessage::Resync));
+    }
+
+    #[test]
+    fn splits_frame_by_topic() {
+        let frame = RealtimeFrame::new(1, data(3, 1.0));
+        let topics = frame.topic_json();
+
+        assert_eq!(topics.len(), TOPICS.len());
+        let (name, memory) = topics.iter().find(|(name, _)| *name == "memory").unwrap();
+        assert_eq!(*name, "memory");
+        let memory: Value = serde_json::from_str(memory).unwrap();
+        assert_eq!(memory["timestamp"], 3);
+        assert_eq!(memory["memory"]["total_kb"], 1024);
+        assert!(memory.get("cpu").is_none());
+    }
+
+    #[test]
+    fn assigns_increasing_sequence_numbers() {
+        let history = RealtimeHistory::new(2);
+        let frames: Vec<_> = (0..3).map(|i| history.record(data(i, 0.0))).collect();
+
+        assert_eq!(seqs(&frames), [1, 2, 3]);
+        assert_eq!(history.event_id(&frames[2]), format!("{}-3", history.boot_id));
+    }
+
+    #[test]
+    fn resumes_after_last_event_id() {
+        let history = RealtimeHistory::new(4);
+        let frames: Vec<_> = (0..3).map(|i| history.record(data(i, 0.0))).collect();
+
+        let resume = history.resume(Some(&history.event_id(&frames[0])));
+        assert_eq!(resume.reset, None);
+        assert_eq!(seqs(&resume.frames), [2, 3]);
+        assert_eq!(resume.last_seq, 1);
+
+        // 已是最新时不补发
+        let resume = history.resume(Some(&history.event_id(&frames[2])));
+        assert_eq!(resume.reset, None);
+        assert!(resume.frames.is_empty());
+        assert_eq!(resume.last_seq, 3);
+    }
+
+    #[test]
+    fn new_client_gets_latest_frame() {
+        let history = RealtimeHistory::new(4);
+        assert!(history.resume(None).frames.is_empty());
+
+        history.record(data(1, 0.0));
+        history.record(data(2, 0.0));
+        let resume = history.resume(None);
+        assert_eq!(resume.reset, None);
+        assert_eq!(seqs(&resume.frames), [2]);
+        assert_eq!(resume.last_seq, 0);
+    }
+
+    #[test]
+    fn resets_when_resume_is_impossible() {
+        let history = RealtimeHistory::new(2);
+        for i in 0..5 {
+            history.record(data(i, 0.0));
+        }
+
+        // 缓冲区只剩 4、5，从 2 之后续传会丢帧
+        let expired = history.resume(Some(&format!("{}-2", history.boot_id)));
+        assert_eq!(expired.reset, Some("expired"));
+        assert_eq!(seqs(&expired.frames), [5]);
+        // 3 之后的帧都还在
+        assert_eq!(history.resume(Some(&format!("{}-3", history.boot_id))).reset, None);
+
+        for id in ["0-3".to_string(), "garbage".to_string(), format!("{}-9", history.boot_id)] {
+            let resume = history.resume(Some(&id));
+            assert_eq!(resume.reset, Some("restarted"), "{}", id);
+            assert_eq!(seqs(&resume.frames), [5]);
+            assert_eq!(resume.last_seq, 0);
+        }
+    }
+}
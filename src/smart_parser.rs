@@ -0,0 +1,380 @@
+use crate::models::*;
+use serde_json::Value;
+
+// smartctl 退出码位掩码：bit0 命令行错误，bit1 无法打开设备
+const SMARTCTL_FATAL_BITS: i32 = 0b11;
+// bit3 磁盘自检判定为失败
+const SMARTCTL_DISK_FAILING_BIT: i32 = 0b1000;
+// `-n standby,<code>` 遇到休眠磁盘时的退出码，按值精确匹配。
+// 不能取 3 这类低位组合（命令行错误加无法打开设备也是 3）；bit0 置位时 smartctl 解析完参数就退出，
+// 不会再带上 bit2 以上的检查结果，因此 255 在真实的执行结果中不会出现
+const SMARTCTL_STANDBY_EXIT: i32 = 255;
+
+// 需要关注的 ATA 属性 ID
+const ATTR_REALLOCATED_SECTORS: u64 = 5;
+const ATTR_POWER_ON_HOURS: u64 = 9;
+const ATTR_TEMPERATURE: u64 = 194;
+const ATTR_PENDING_SECTORS: u64 = 197;
+const ATTR_OFFLINE_UNCORRECTABLE: u64 = 198;
+
+/// smartctl 退出码是否表示完全没有拿到数据（磁盘休眠除外）
+pub fn is_smartctl_fatal(exit_code: Option<i32>) -> bool {
+    exit_code.is_none_or(|code| code != SMARTCTL_STANDBY_EXIT && code & SMARTCTL_FATAL_BITS != 0)
+}
+
+/// 不唤醒休眠磁盘的 `-n` 参数值
+pub fn smartctl_standby_arg() -> String {
+    format!("standby,{}", SMARTCTL_STANDBY_EXIT)
+}
+
+/// smartctl 是否因为磁盘休眠而没有读取
+pub fn is_smartctl_standby(exit_code: Option<i32>) -> bool {
+    exit_code == Some(SMARTCTL_STANDBY_EXIT)
+}
+
+/// 休眠磁盘的读数：沿用上一次的结果，没有时只有设备名
+pub fn standby_smart_info(device: &str, last: Option<&SmartInfo>) -> SmartInfo {
+    let mut info = last.cloned().unwrap_or_else(|| SmartInfo {
+        device: device.to_string(),
+        model: None,
+        serial: None,
+        protocol: "Unknown".to_string(),
+        health_status: "UNKNOWN".to_string(),
+        temperature_celsius: None,
+        power_on_hours: None,
+        reallocated_sectors: None,
+        pending_sectors: None,
+        offline_uncorrectable: None,
+        nvme_percentage_used: None,
+        nvme_available_spare: None,
+        nvme_media_errors: None,
+        alerts: Vec::new(),
+        standby: false,
+    });
+    info.standby = true;
+    info
+}
+
+/// 解析 `smartctl --scan --json` 的输出，返回 (设备路径, 设备类型)
+pub fn parse_smartctl_scan(output: &str) -> Vec<(String, String)> {
+    let json: Value = match serde_json::from_str(output) {
+        Ok(json) => json,
+        Err(_) => return Vec::new(),
+    };
+
+    json.get("devices")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|device| {
+            let name = device.get("name")?.as_str()?.to_string();
+            let device_type = device.get("type").and_then(|v| v.as_str()).unwrap_or("auto").to_string();
+            Some((name, device_type))
+        })
+        .collect()
+}
+
+/// 解析 `smartctl -a --json <device>` 的输出
+pub fn parse_smartctl_device(output: &str, exit_code: Option<i32>) -> Option<SmartInfo> {
+    let json: Value = serde_json::from_str(output).ok()?;
+    let device = json.get("device")?;
+
+    let device_name = device.get("name")?.as_str()?.to_string();
+    let protocol = device.get("protocol").and_then(|v| v.as_str()).unwrap_or("Unknown").to_string();
+
+    let smart_passed = json.get("smart_status")
+        .and_then(|s| s.get("passed"))
+        .and_then(|v| v.as_bool());
+    let failing = exit_code.is_some_and(|code| code & SMARTCTL_DISK_FAILING_BIT != 0);
+    let health_status = match (smart_passed, failing) {
+        (_, true) | (Some(false), _) => "FAILED",
+        (Some(true), false) => "PASSED",
+        (None, false) => "UNKNOWN",
+    }.to_string();
+
+    let mut info = SmartInfo {
+        device: device_name,
+        model: json.get("model_name").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        serial: json.get("serial_number").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        protocol,
+        health_status,
+        temperature_celsius: json.get("temperature")
+            .and_then(|t| t.get("current"))
+            .and_then(|v| v.as_f64())
+            .map(|t| t as f32),
+        power_on_hours: json.get("power_on_time")
+            .and_then(|t| t.get("hours"))
+            .and_then(|v| v.as_u64()),
+        reallocated_sectors: None,
+        pending_sectors: None,
+        offline_uncorrectable: None,
+        nvme_percentage_used: None,
+        nvme_available_spare: None,
+        nvme_media_errors: None,
+        alerts: Vec::new(),
+        standby: false,
+    };
+
+    if let Some(table) = json.get("ata_smart_attributes")
+        .and_then(|a| a.get("table"))
+        .and_then(|t| t.as_array())
+    {
+        parse_ata_attributes(table, &mut info);
+    }
+
+    if let Some(log) = json.get("nvme_smart_health_information_log") {
+        parse_nvme_health_log(log, &mut info);
+    }
+
+    if info.health_status == "FAILED" {
+        info.alerts.push(SmartAlert {
+            metric: "health_status".to_string(),
+            severity: "critical".to_string(),
+            value: 0.0,
+            threshold: None,
+            message: "SMART overall-health self-assessment failed".to_string(),
+        });
+    }
+
+    Some(info)
+}
+
+fn parse_ata_attributes(table: &[Value], info: &mut SmartInfo) {
+    for attr in table {
+        let Some(id) = attr.get("id").and_then(|v| v.as_u64()) else {
+            continue;
+        };
+        let name = attr.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown");
+        let raw = attr.get("raw").and_then(|r| r.get("value")).and_then(|v| v.as_u64());
+
+        match id {
+            ATTR_REALLOCATED_SECTORS => info.reallocated_sectors = raw,
+            ATTR_PENDING_SECTORS => info.pending_sectors = raw,
+            ATTR_OFFLINE_UNCORRECTABLE => info.offline_uncorrectable = raw,
+            // 部分盘的 power_on_time 字段缺失，退回到属性表
+            ATTR_POWER_ON_HOURS if info.power_on_hours.is_none() => info.power_on_hours = raw,
+            // 温度原始值的高位可能带有最小/最大温度，只取低 16 位
+            ATTR_TEMPERATURE if info.temperature_celsius.is_none() => {
+                info.temperature_celsius = raw.map(|r| (r & 0xffff) as f32);
+            }
+            _ => {}
+        }
+
+        // 归一化值低于厂商阈值（当前或曾经）
+        let when_failed = attr.get("when_failed").and_then(|v| v.as_str()).unwrap_or("");
+        if !when_failed.is_empty() {
+            let value = attr.get("value").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let threshold = attr.get("thresh").and_then(|v| v.as_f64());
+            info.alerts.push(SmartAlert {
+                metric: name.to_string(),
+                severity: if when_failed == "now" { "critical" } else { "warning" }.to_string(),
+                value,
+                threshold,
+                message: format!("Attribute {} ({}) failed {}", id, name, when_failed),
+            });
+        }
+    }
+
+    for (metric, count) in [
+        ("reallocated_sectors", info.reallocated_sectors),
+        ("pending_sectors", info.pending_sectors),
+        ("offline_uncorrectable", info.offline_uncorrectable),
+    ] {
+        if let Some(count) = count.filter(|c| *c > 0) {
+            info.alerts.push(SmartAlert {
+                metric: metric.to_string(),
+                severity: "warning".to_string(),
+                value: count as f64,
+                threshold: Some(0.0),
+                message: format!("{} {}", count, metric.replace('_', " ")),
+            });
+        }
+    }
+}
+
+fn parse_nvme_health_log(log: &Value, info: &mut SmartInfo) {
+    let field = |name: &str| log.get(name).and_then(|v| v.as_u64());
+
+    info.nvme_percentage_used = field("percentage_used").map(|v| v.min(255) as u8);
+    info.nvme_available_spare = field("available_spare").map(|v| v.min(255) as u8);
+    info.nvme_media_errors = field("media_errors");
+    if info.power_on_hours.is_none() {
+        info.power_on_hours = field("power_on_hours");
+    }
+    if info.temperature_celsius.is_none() {
+        info.temperature_celsius = field("temperature").map(|t| t as f32);
+    }
+
+    if let Some(warning) = field("critical_warning").filter(|w| *w != 0) {
+        info.alerts.push(SmartAlert {
+            metric: "critical_warning".to_string(),
+            severity: "critical".to_string(),
+            value: warning as f64,
+            threshold: Some(0.0),
+            message: format!("NVMe critical warning flags: {:#04x}", warning),
+        });
+    }
+
+    if let (Some(spare), Some(threshold)) = (field("available_spare"), field("available_spare_threshold"))
+        && spare <= threshold
+    {
+        info.alerts.push(SmartAlert {
+            metric: "available_spare".to_string(),
+            severity: "critical".to_string(),
+            value: spare as f64,
+            threshold: Some(threshold as f64),
+            message: format!("Available spare {}% at or below threshold {}%", spare, threshold),
+        });
+    }
+
+    if let Some(used) = info.nvme_percentage_used.filter(|u| *u >= 90) {
+        info.alerts.push(SmartAlert {
+            metric: "percentage_used".to_string(),
+            severity: if used >= 100 { "critical" } else { "warning" }.to_string(),
+            value: used as f64,
+            threshold: Some(90.0),
+            message: format!("NVMe wear level at {}%", used),
+        });
+    }
+
+    if let Some(errors) = info.nvme_media_errors.filter(|e| *e > 0) {
+        info.alerts.push(SmartAlert {
+            metric: "media_errors".to_string(),
+            severity: "warning".to_string(),
+            value: errors as f64,
+            threshold: Some(0.0),
+            message: format!("{} media and data integrity errors", errors),
+        });
+    }
+}
+
+/// smartctl 设备路径是否对应某个块设备，/dev/nvme0 对应 nvme0n1
+pub fn smart_device_matches(smart_device: &str, block_device: &str) -> bool {
+    let smart_name = smart_device.trim_start_matches("/dev/");
+    if smart_name == block_device {
+        return true;
+    }
+    // NVMe 控制器 nvme0 下的命名空间为 nvme0n1、nvme0n2 ...
+    smart_name.starts_with("nvme")
+        && block_device
+            .strip_prefix(smart_name)
+            .and_then(|rest| rest.strip_prefix('n'))
+            .is_some_and(|ns| !ns.is_empty() && ns.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATA: &str = include_str!("../tests/fixtures/smartctl/ata.json");
+    const NVME: &str = include_str!("../tests/fixtures/smartctl/nvme.json");
+
+    fn alert<'a>(info: &'a SmartInfo, metric: &str) -> Option<&'a SmartAlert> {
+        info.alerts.iter().find(|a| a.metric == metric)
+    }
+
+    #[test]
+    fn parses_scan_output() {
+        let devices = parse_smartctl_scan(include_str!("../tests/fixtures/smartctl/scan.json"));
+
+        assert_eq!(
+            devices,
+            vec![
+                ("/dev/sda".to_string(), "sat".to_string()),
+                ("/dev/nvme0".to_string(), "nvme".to_string()),
+            ]
+        );
+        assert!(parse_smartctl_scan("not json").is_empty());
+    }
+
+    #[test]
+    fn parses_ata_attributes() {
+        let info = parse_smartctl_device(ATA, Some(0)).unwrap();
+
+        assert_eq!(info.device, "/dev/sda");
+        assert_eq!(info.protocol, "ATA");
+        assert_eq!(info.model.as_deref(), Some("WDC WD40EFRX-68N32N0"));
+        assert_eq!(info.health_status, "PASSED");
+        // 没有 temperature/power_on_time 字段时取属性表，温度只取原始值的低 16 位
+        assert_eq!(info.temperature_celsius, Some(38.0));
+        assert_eq!(info.power_on_hours, Some(33912));
+        assert_eq!(info.reallocated_sectors, Some(8));
+        assert_eq!(info.pending_sectors, Some(0));
+        assert_eq!(info.offline_uncorrectable, Some(0));
+        assert!(!info.standby);
+
+        let spin_retry = alert(&info, "Spin_Retry_Count").unwrap();
+        assert_eq!(spin_retry.severity, "warning");
+        assert_eq!(spin_retry.threshold, Some(97.0));
+        let reallocated = alert(&info, "reallocated_sectors").unwrap();
+        assert_eq!(reallocated.value, 8.0);
+        assert!(alert(&info, "pending_sectors").is_none());
+        assert_eq!(info.alerts.len(), 2);
+    }
+
+    #[test]
+    fn parses_nvme_health_log() {
+        let info = parse_smartctl_device(NVME, Some(0)).unwrap();
+
+        assert_eq!(info.protocol, "NVMe");
+        assert_eq!(info.health_status, "PASSED");
+        assert_eq!(info.temperature_celsius, Some(41.0));
+        assert_eq!(info.power_on_hours, Some(12034));
+        assert_eq!(info.nvme_percentage_used, Some(93));
+        assert_eq!(info.nvme_available_spare, Some(100));
+        assert_eq!(info.nvme_media_errors, Some(2));
+        assert_eq!(info.reallocated_sectors, None);
+
+        assert_eq!(alert(&info, "percentage_used").unwrap().severity, "warning");
+        assert_eq!(alert(&info, "media_errors").unwrap().value, 2.0);
+        assert!(alert(&info, "available_spare").is_none());
+        assert!(alert(&info, "critical_warning").is_none());
+    }
+
+    #[test]
+    fn failing_exit_bit_marks_drive_failed() {
+        let info = parse_smartctl_device(ATA, Some(SMARTCTL_DISK_FAILING_BIT)).unwrap();
+
+        assert_eq!(info.health_status, "FAILED");
+        assert_eq!(alert(&info, "health_status").unwrap().severity, "critical");
+    }
+
+    #[test]
+    fn standby_exit_code_is_not_fatal() {
+        assert!(is_smartctl_standby(Some(SMARTCTL_STANDBY_EXIT)));
+        assert!(!is_smartctl_fatal(Some(SMARTCTL_STANDBY_EXIT)));
+        assert_eq!(smartctl_standby_arg(), "standby,255");
+        // 命令行错误加无法打开设备不是休眠
+        assert!(!is_smartctl_standby(Some(0b11)));
+        assert!(is_smartctl_fatal(Some(0b11)));
+        assert!(is_smartctl_fatal(Some(0b10)));
+        assert!(is_smartctl_fatal(None));
+        assert!(!is_smartctl_standby(Some(0b100_0000)));
+        assert!(!is_smartctl_fatal(Some(0b100_0000)));
+        assert!(!is_smartctl_fatal(Some(SMARTCTL_DISK_FAILING_BIT)));
+    }
+
+    #[test]
+    fn standby_keeps_last_reading() {
+        let last = parse_smartctl_device(ATA, Some(0)).unwrap();
+
+        let info = standby_smart_info("/dev/sda", Some(&last));
+        assert!(info.standby);
+        assert_eq!(info.temperature_celsius, Some(38.0));
+        assert_eq!(info.health_status, "PASSED");
+
+        let info = standby_smart_info("/dev/sdb", None);
+        assert!(info.standby);
+        assert_eq!(info.device, "/dev/sdb");
+        assert_eq!(info.health_status, "UNKNOWN");
+    }
+
+    #[test]
+    fn matches_nvme_namespaces() {
+        assert!(smart_device_matches("/dev/sda", "sda"));
+        assert!(smart_device_matches("/dev/nvme0", "nvme0n1"));
+        assert!(!smart_device_matches("/dev/nvme0", "nvme0n1p1"));
+        assert!(!smart_device_matches("/dev/nvme1", "nvme10n1"));
+        assert!(!smart_device_matches("/dev/sda", "sdb"));
+    }
+}
@@ -0,0 +1,508 @@
+use crate::models::*;
+use std::collections::HashMap;
+
+/// 解析 `/proc/mdstat`
+pub fn parse_mdstat(content: &str) -> Vec<StoragePoolInfo> {
+    let mut pools = Vec::new();
+    let mut lines = content.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        // 阵列以 "md0 : active raid1 sdb1[1] sda1[0]" 开头
+        let Some((name, rest)) = line.split_once(" : ") else {
+            continue;
+        };
+        let name = name.trim();
+        if !name.starts_with("md") {
+            continue;
+        }
+
+        let mut words = rest.split_whitespace().peekable();
+        let state = words.next().unwrap_or("unknown").to_string();
+        // "active (auto-read-only) raid1 ..." 之类的附加状态
+        while words.peek().is_some_and(|w| w.starts_with('(')) {
+            words.next();
+        }
+        let level = words.peek().filter(|w| !w.contains('[')).map(|w| w.to_string());
+        if level.is_some() {
+            words.next();
+        }
+
+        let members: Vec<PoolMember> = words.filter_map(parse_md_member).collect();
+
+        let mut pool = StoragePoolInfo {
+            name: name.to_string(),
+            pool_type: "mdraid".to_string(),
+            level,
+            health: state,
+            degraded: false,
+            size_bytes: None,
+            allocated_bytes: None,
+            free_bytes: None,
+            members,
+            operation: None,
+            errors: None,
+        };
+
+        // 后续缩进行：容量、[2/2] [UU]、同步进度
+        while let Some(detail) = lines.peek() {
+            if detail.trim().is_empty() || !detail.starts_with(char::is_whitespace) {
+                break;
+            }
+            let detail = lines.next().unwrap_or_default().trim();
+
+            if let Some(blocks) = detail.split_whitespace().next().and_then(|b| b.parse::<u64>().ok())
+                && detail.contains("blocks")
+            {
+                pool.size_bytes = Some(blocks * 1024);
+            }
+
+            if let Some(status) = detail.split_whitespace().find(|w| w.starts_with('[') && w.contains('/')) {
+                let counts: Vec<u32> = status
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split('/')
+                    .filter_map(|n| n.parse().ok())
+                    .collect();
+                if let [total, active] = counts[..] {
+                    pool.degraded = active < total;
+                }
+            }
+
+            if let Some(operation) = parse_md_operation(detail) {
+                pool.operation = Some(operation);
+            }
+        }
+
+        if pool.members.iter().any(|m| m.state == "FAULTY") {
+            pool.degraded = true;
+        }
+        if pool.degraded {
+            pool.health = format!("{} (degraded)", pool.health);
+        }
+
+        pools.push(pool);
+    }
+
+    pools
+}
+
+// "sdb1[1](F)" -> 成员 sdb1，状态 FAULTY
+fn parse_md_member(word: &str) -> Option<PoolMember> {
+    let (name, rest) = word.split_once('[')?;
+    let state = match rest.split_once('(').map(|(_, flag)| flag.trim_end_matches(')')) {
+        Some("F") => "FAULTY",
+        Some("S") => "SPARE",
+        Some("W") => "WRITE-MOSTLY",
+        Some("R") => "REPLACEMENT",
+        _ => "ACTIVE",
+    };
+    Some(PoolMember {
+        name: name.to_string(),
+        state: state.to_string(),
+        read_errors: 0,
+        write_errors: 0,
+        checksum_errors: 0,
+    })
+}
+
+// "[=>....]  recovery =  8.5% (166373376/1953382400) finish=143.9min speed=206947K/sec"
+fn parse_md_operation(detail: &str) -> Option<PoolOperation> {
+    let kind = ["recovery", "resync", "reshape", "check", "repair"]
+        .into_iter()
+        .find(|kind| detail.contains(&format!("{} =", kind)))?;
+
+    // 进度条 [=>...] 本身也含有 '='，从 "recovery =" 之后开始截取
+    let marker = format!("{} =", kind);
+    let progress_percent = detail
+        .split_once(&marker)
+        .and_then(|(_, p)| p.split('%').next())
+        .and_then(|p| p.trim().parse::<f32>().ok());
+    let field = |key: &str| {
+        detail
+            .split_whitespace()
+            .find_map(|w| w.strip_prefix(key))
+            .map(|v| v.to_string())
+    };
+
+    Some(PoolOperation {
+        kind: kind.to_string(),
+        in_progress: true,
+        progress_percent,
+        eta: field("finish="),
+        speed: field("speed="),
+        summary: detail.to_string(),
+    })
+}
+
+/// 解析 `zpool list -Hp -o name,size,alloc,free,health`，返回 名称 -> (大小, 已用, 可用, 健康状态)
+pub fn parse_zpool_list(output: &str) -> HashMap<String, (u64, u64, u64, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() < 5 {
+                return None;
+            }
+            Some((
+                parts[0].to_string(),
+                (
+                    parts[1].parse().unwrap_or(0),
+                    parts[2].parse().unwrap_or(0),
+                    parts[3].parse().unwrap_or(0),
+                    parts[4].to_string(),
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// 解析 `zpool status -p` 的输出
+pub fn parse_zpool_status(output: &str) -> Vec<StoragePoolInfo> {
+    let mut pools = Vec::new();
+    let mut current: Option<StoragePoolInfo> = None;
+    let mut in_scan = false;
+    let mut in_config = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        if let Some(name) = trimmed.strip_prefix("pool:") {
+            if let Some(pool) = current.take() {
+                pools.push(pool);
+            }
+            current = Some(StoragePoolInfo {
+                name: name.trim().to_string(),
+                pool_type: "zfs".to_string(),
+                level: None,
+                health: "UNKNOWN".to_string(),
+                degraded: false,
+                size_bytes: None,
+                allocated_bytes: None,
+                free_bytes: None,
+                members: Vec::new(),
+                operation: None,
+                errors: None,
+            });
+            in_scan = false;
+            in_config = false;
+            continue;
+        }
+
+        let Some(pool) = current.as_mut() else {
+            continue;
+        };
+
+        if let Some(state) = trimmed.strip_prefix("state:") {
+            pool.health = state.trim().to_string();
+            pool.degraded = pool.health != "ONLINE";
+            in_scan = false;
+        } else if let Some(scan) = trimmed.strip_prefix("scan:") {
+            pool.operation = parse_zfs_scan_line(scan.trim());
+            in_scan = true;
+        } else if trimmed.starts_with("config:") {
+            in_config = true;
+            in_scan = false;
+        } else if let Some(errors) = trimmed.strip_prefix("errors:") {
+            pool.errors = Some(errors.trim().to_string());
+            in_config = false;
+        } else if in_scan && !trimmed.is_empty() {
+            // 扫描进度的续行："100G resilvered, 38.52% done, 02:10:11 to go"
+            if let Some(operation) = pool.operation.as_mut() {
+                update_zfs_scan_progress(operation, trimmed);
+            }
+        } else if in_config && !trimmed.is_empty() {
+            parse_zpool_config_row(pool, trimmed);
+        }
+    }
+
+    if let Some(pool) = current.take() {
+        pools.push(pool);
+    }
+
+    pools
+}
+
+fn parse_zfs_scan_line(scan: &str) -> Option<PoolOperation> {
+    if scan.starts_with("none requested") {
+        return None;
+    }
+
+    let kind = if scan.starts_with("resilver") { "resilver" } else { "scrub" };
+    Some(PoolOperation {
+        kind: kind.to_string(),
+        in_progress: scan.contains("in progress"),
+        progress_percent: None,
+        eta: None,
+        speed: None,
+        summary: scan.to_string(),
+    })
+}
+
+fn update_zfs_scan_progress(operation: &mut PoolOperation, line: &str) {
+    for part in line.split(',').map(|p| p.trim()) {
+        if let Some(percent) = part.strip_suffix("% done") {
+            operation.progress_percent = percent.trim().parse().ok();
+        } else if let Some(eta) = part.strip_suffix(" to go") {
+            operation.eta = Some(eta.trim().to_string());
+        } else if let Some((_, speed)) = part.split_once(" issued at ") {
+            operation.speed = Some(speed.split_whitespace().next().unwrap_or(speed).to_string());
+        }
+    }
+}
+
+// 配置表：NAME STATE READ WRITE CKSUM，第一行为池本身
+fn parse_zpool_config_row(pool: &mut StoragePoolInfo, row: &str) {
+    let parts: Vec<&str> = row.split_whitespace().collect();
+    if parts.first() == Some(&"NAME") || ["logs", "cache", "spares", "special", "dedup"].contains(&row) {
+        return;
+    }
+
+    let counter = |i: usize| parts.get(i).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+    let state = parts.get(1).unwrap_or(&"UNKNOWN").to_string();
+
+    if parts[0] == pool.name {
+        return;
+    }
+
+    // mirror-0、raidz1-0 这类 vdev 决定池的冗余级别
+    if pool.level.is_none()
+        && let Some(level) = ["mirror", "raidz1", "raidz2", "raidz3", "draid"]
+            .iter()
+            .find(|level| parts[0].starts_with(*level))
+    {
+        pool.level = Some(level.to_string());
+    }
+
+    pool.members.push(PoolMember {
+        name: parts[0].to_string(),
+        state,
+        read_errors: counter(2),
+        write_errors: counter(3),
+        checksum_errors: counter(4),
+    });
+}
+
+/// 解析 `btrfs device stats <mount>` 的输出
+pub fn parse_btrfs_device_stats(mount_point: &str, output: &str) -> Option<StoragePoolInfo> {
+    let mut members: Vec<PoolMember> = Vec::new();
+
+    for line in output.lines() {
+        // [/dev/sda1].write_io_errs    0
+        let Some((key, value)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let Some((device, counter)) = key.trim_start_matches('[').split_once("].") else {
+            continue;
+        };
+        let value = value.trim().parse::<u64>().unwrap_or(0);
+
+        let index = match members.iter().position(|m| m.name == device) {
+            Some(index) => index,
+            None => {
+                members.push(PoolMember {
+                    name: device.to_string(),
+                    state: "OK".to_string(),
+                    read_errors: 0,
+                    write_errors: 0,
+                    checksum_errors: 0,
+                });
+                members.len() - 1
+            }
+        };
+        let member = &mut members[index];
+        match counter {
+            "read_io_errs" => member.read_errors += value,
+            "write_io_errs" | "flush_io_errs" => member.write_errors += value,
+            "corruption_errs" | "generation_errs" => member.checksum_errors += value,
+            _ => {}
+        }
+        if value > 0 {
+            member.state = "ERRORS".to_string();
+        }
+    }
+
+    if members.is_empty() {
+        return None;
+    }
+
+    let has_errors = members.iter().any(|m| m.state != "OK");
+    Some(StoragePoolInfo {
+        name: mount_point.to_string(),
+        pool_type: "btrfs".to_string(),
+        level: None,
+        health: if has_errors { "ERRORS" } else { "OK" }.to_string(),
+        degraded: false,
+        size_bytes: None,
+        allocated_bytes: None,
+        free_bytes: None,
+        members,
+        operation: None,
+        errors: None,
+    })
+}
+
+/// 解析 `btrfs scrub status <mount>` 的输出
+pub fn parse_btrfs_scrub_status(output: &str) -> Option<PoolOperation> {
+    let mut status = None;
+    let mut progress_percent = None;
+    let mut eta = None;
+    let mut speed = None;
+    let mut summary = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Status" => status = Some(value.to_string()),
+            "Time left" => eta = Some(value.to_string()),
+            "Rate" => speed = Some(value.to_string()),
+            "Error summary" => summary = Some(value.to_string()),
+            // "Bytes scrubbed:   12.34GiB  (45.67%)"
+            "Bytes scrubbed" => {
+                progress_percent = value
+                    .split('(')
+                    .nth(1)
+                    .and_then(|p| p.split('%').next())
+                    .and_then(|p| p.trim().parse::<f32>().ok());
+            }
+            _ => {}
+        }
+    }
+
+    let status = status?;
+    Some(PoolOperation {
+        kind: "scrub".to_string(),
+        in_progress: status == "running",
+        progress_percent,
+        eta,
+        speed,
+        summary: summary.map(|s| format!("{}, {}", status, s)).unwrap_or(status),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool<'a>(pools: &'a [StoragePoolInfo], name: &str) -> &'a StoragePoolInfo {
+        pools.iter().find(|p| p.name == name).unwrap()
+    }
+
+    #[test]
+    fn parses_healthy_md_array() {
+        let pools = parse_mdstat(include_str!("../tests/fixtures/storage-pools/mdstat"));
+        assert_eq!(pools.len(), 3);
+
+        let md0 = pool(&pools, "md0");
+        assert_eq!(md0.level.as_deref(), Some("raid1"));
+        assert_eq!(md0.health, "active");
+        assert!(!md0.degraded);
+        assert_eq!(md0.size_bytes, Some(1953382400 * 1024));
+        assert_eq!(md0.members.len(), 2);
+        assert!(md0.members.iter().all(|m| m.state == "ACTIVE"));
+        assert!(md0.operation.is_none());
+    }
+
+    #[test]
+    fn marks_md_array_with_failed_member_degraded() {
+        let pools = parse_mdstat(include_str!("../tests/fixtures/storage-pools/mdstat"));
+
+        let md1 = pool(&pools, "md1");
+        assert_eq!(md1.level.as_deref(), Some("raid5"));
+        assert!(md1.degraded);
+        assert_eq!(md1.health, "active (degraded)");
+        let faulty: Vec<&str> = md1.members.iter().filter(|m| m.state == "FAULTY").map(|m| m.name.as_str()).collect();
+        assert_eq!(faulty, ["sdf1"]);
+    }
+
+    #[test]
+    fn parses_md_recovery_progress() {
+        let pools = parse_mdstat(include_str!("../tests/fixtures/storage-pools/mdstat"));
+
+        let md2 = pool(&pools, "md2");
+        assert!(md2.degraded);
+        let operation = md2.operation.as_ref().unwrap();
+        assert_eq!(operation.kind, "recovery");
+        assert!(operation.in_progress);
+        assert_eq!(operation.progress_percent, Some(8.5));
+        assert_eq!(operation.eta.as_deref(), Some("143.9min"));
+        assert_eq!(operation.speed.as_deref(), Some("206947K/sec"));
+    }
+
+    #[test]
+    fn parses_resilvering_zpool() {
+        let pools = parse_zpool_status(include_str!("../tests/fixtures/storage-pools/zpool-status"));
+        assert_eq!(pools.len(), 2);
+
+        let backup = pool(&pools, "backup");
+        assert_eq!(backup.health, "DEGRADED");
+        assert!(backup.degraded);
+        assert_eq!(backup.level.as_deref(), Some("mirror"));
+        assert_eq!(backup.errors.as_deref(), Some("No known data errors"));
+
+        let operation = backup.operation.as_ref().unwrap();
+        assert_eq!(operation.kind, "resilver");
+        assert!(operation.in_progress);
+        assert_eq!(operation.progress_percent, Some(38.52));
+        assert_eq!(operation.eta.as_deref(), Some("00:05:41"));
+        assert_eq!(operation.speed.as_deref(), Some("480M/s"));
+
+        let faulted = backup.members.iter().find(|m| m.name == "ata-WDC_WD40EFRX_2").unwrap();
+        assert_eq!(faulted.state, "FAULTED");
+        assert_eq!(faulted.read_errors, 3);
+        assert_eq!(faulted.write_errors, 12);
+    }
+
+    #[test]
+    fn parses_online_zpool_with_finished_scrub() {
+        let pools = parse_zpool_status(include_str!("../tests/fixtures/storage-pools/zpool-status"));
+
+        let tank = pool(&pools, "tank");
+        assert_eq!(tank.health, "ONLINE");
+        assert!(!tank.degraded);
+        assert_eq!(tank.level.as_deref(), Some("raidz2"));
+        // 第一行是池本身，不算成员
+        assert_eq!(tank.members.len(), 5);
+        assert_eq!(tank.members.iter().find(|m| m.name == "sdd").unwrap().checksum_errors, 2);
+
+        let operation = tank.operation.as_ref().unwrap();
+        assert_eq!(operation.kind, "scrub");
+        assert!(!operation.in_progress);
+    }
+
+    #[test]
+    fn parses_zpool_list() {
+        let pools = parse_zpool_list(include_str!("../tests/fixtures/storage-pools/zpool-list"));
+
+        assert_eq!(
+            pools.get("backup"),
+            Some(&(4000787030016, 280246616064, 3720540413952, "DEGRADED".to_string()))
+        );
+        assert_eq!(pools.len(), 2);
+    }
+
+    #[test]
+    fn parses_btrfs_device_stats() {
+        let pool = parse_btrfs_device_stats("/data", include_str!("../tests/fixtures/storage-pools/btrfs-device-stats")).unwrap();
+
+        assert_eq!(pool.name, "/data");
+        assert_eq!(pool.health, "ERRORS");
+        assert_eq!(pool.members[0].state, "OK");
+        let sdb = &pool.members[1];
+        assert_eq!(sdb.state, "ERRORS");
+        assert_eq!((sdb.read_errors, sdb.write_errors, sdb.checksum_errors), (5, 3, 3));
+        assert!(parse_btrfs_device_stats("/data", "").is_none());
+    }
+
+    #[test]
+    fn parses_running_btrfs_scrub() {
+        let operation = parse_btrfs_scrub_status(include_str!("../tests/fixtures/storage-pools/btrfs-scrub-status")).unwrap();
+
+        assert!(operation.in_progress);
+        assert_eq!(operation.progress_percent, Some(31.66));
+        assert_eq!(operation.eta.as_deref(), Some("0:27:02"));
+        assert_eq!(operation.speed.as_deref(), Some("530.45MiB/s"));
+        assert_eq!(operation.summary, "running, no errors found");
+    }
+}